@@ -30,7 +30,7 @@ use crate::virtualenv::{
     virtualenv_python_executable, CondaEnvironmentKind,
 };
 #[cfg(windows)]
-use crate::windows_registry::{registry_pythons, WindowsPython};
+use crate::windows_registry::{registry_pythons, RegistryCandidate, WindowsPython};
 use crate::{Interpreter, PythonVersion};
 
 /// A request to find a Python installation.
@@ -348,16 +348,22 @@ fn python_executables_from_installed<'a>(
         #[cfg(windows)]
         {
             // Skip interpreter probing if we already know the version doesn't match.
+            // Entries without a version are probed. Pre-releases are kept here, they are only
+            // skipped after probing, when there is a stable alternative.
             let version_filter = move |entry: &WindowsPython| {
-                if let Some(found) = &entry.version {
-                    // Some distributions emit the patch version (example: `SysVersion: 3.9`)
-                    if found.string.chars().filter(|c| *c == '.').count() == 1 {
-                        version.matches_major_minor(found.major(), found.minor())
-                    } else {
-                        version.matches_version(found)
+                entry.version().is_none() || entry.matches_version_request(version, true)
+            };
+            // Skip interpreter probing if the registry tells that it's another implementation.
+            let implementation_filter = move |candidate: &RegistryCandidate| {
+                match (implementation, candidate.implementation()) {
+                    (Some(requested), Some(registered)) if *requested != registered => {
+                        debug!(
+                            "Skipping registry entry `{}`: {requested} was requested, found {registered}",
+                            candidate.path().user_display()
+                        );
+                        false
                     }
-                } else {
-                    true
+                    _ => true,
                 }
             };
 
@@ -365,16 +371,23 @@ fn python_executables_from_installed<'a>(
                 .is_none()
                 .then(|| {
                     registry_pythons()
-                        .map(|entries| {
+                        .map(|mut entries| {
+                            // Without an explicit request, prefer the default designated by an
+                            // administrator.
+                            if *version == VersionRequest::Default {
+                                entries.sort_by_key(|entry| !entry.is_default());
+                            }
                             entries
                                 .into_iter()
                                 .filter(version_filter)
-                                .map(|entry| (PythonSource::Registry, entry.path))
-                                .chain(
-                                    find_microsoft_store_pythons()
-                                        .filter(version_filter)
-                                        .map(|entry| (PythonSource::MicrosoftStore, entry.path)),
-                                )
+                                .map(|entry| entry.into_discovery_candidate(PythonSource::Registry))
+                                .chain(find_microsoft_store_pythons().filter(version_filter).map(
+                                    |entry| {
+                                        entry.into_discovery_candidate(PythonSource::MicrosoftStore)
+                                    },
+                                ))
+                                .filter(implementation_filter)
+                                .map(<(PythonSource, PathBuf)>::from)
                         })
                         .map_err(Error::from)
                 })
@@ -2163,7 +2176,7 @@ impl VersionRequest {
     ///
     /// WARNING: Use [`VersionRequest::matches_interpreter`] too. This method is only suitable to
     /// avoid querying interpreters if it's clear it cannot fulfill the request.
-    fn matches_major_minor(&self, major: u8, minor: u8) -> bool {
+    pub(crate) fn matches_major_minor(&self, major: u8, minor: u8) -> bool {
         match self {
             Self::Any | Self::Default => true,
             Self::Major(self_major, _) => *self_major == major,
@@ -333,6 +333,10 @@ impl PythonInstallationKey {
     }
 
     /// The version in `x.y.z` format.
+    ///
+    /// This is the `SysVersion` of the registry entry on Windows, so it must remain parseable as
+    /// a [`PythonVersion`] with the same major, minor and patch version. The pre-release is
+    /// omitted, it's recorded in the `Version` value instead.
     pub fn sys_version(&self) -> String {
         format!("{}.{}.{}", self.major, self.minor, self.patch)
     }
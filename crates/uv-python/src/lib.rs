@@ -22,6 +22,9 @@ pub use crate::version_files::{
 };
 pub use crate::virtualenv::{Error as VirtualEnvError, PyVenvConfiguration, VirtualEnvironment};
 
+#[cfg(windows)]
+pub use crate::microsoft_store::microsoft_store_offered_versions;
+
 mod cpuinfo;
 mod discovery;
 pub mod downloads;
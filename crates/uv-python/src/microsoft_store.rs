@@ -3,6 +3,7 @@
 //!
 //! Effectively a port of <https://github.com/python/cpython/blob/58ce131037ecb34d506a613f21993cde2056f628/PC/launcher2.c#L1744>
 
+use crate::discovery::is_windows_store_shim;
 use crate::windows_registry::WindowsPython;
 use crate::PythonVersion;
 use itertools::Either;
@@ -18,6 +19,10 @@ struct MicrosoftStorePython {
     version: &'static str,
 }
 
+/// The suffix of the family names of the releases made through the Store, as opposed to the
+/// side-loadable releases.
+const STORE_PUBLISHER_ID: &str = "_qbz5n2kfra8p0";
+
 /// List of known Microsoft Store Pythons.
 ///
 /// Copied from <https://github.com/python/cpython/blob/58ce131037ecb34d506a613f21993cde2056f628/PC/launcher2.c#L1963-L1985>,
@@ -108,12 +113,49 @@ pub(crate) fn find_microsoft_store_pythons() -> impl Iterator<Item = WindowsPyth
                 let path = windows_apps
                     .join(store_python.family_name)
                     .join("python.exe");
-                WindowsPython {
+                WindowsPython::new(
                     path,
                     // All versions are constants, we know they are valid.
-                    version: Some(PythonVersion::from_str(store_python.version).unwrap()),
-                }
+                    Some(PythonVersion::from_str(store_python.version).unwrap()),
+                )
             })
             .filter(|windows_python| windows_python.path.is_file()),
     )
 }
+
+/// The versions of Python that can be installed from the Microsoft Store, newest first, for
+/// suggesting an installation when no interpreter was found.
+///
+/// This is best effort: The app execution alias only redirects to the Store installer, without
+/// telling which versions the Store offers. If the `python.exe` alias in `WindowsApps` is the
+/// Store installer shim, we report the known releases made through the Store that aren't
+/// installed yet. Otherwise, e.g., if the alias is disabled, the list is empty.
+pub fn microsoft_store_offered_versions() -> Vec<PythonVersion> {
+    let Ok(local_app_data) = env::var(EnvVars::LOCALAPPDATA) else {
+        debug!("`LOCALAPPDATA` not set, ignoring Microsoft store Pythons");
+        return Vec::new();
+    };
+
+    let windows_apps = PathBuf::from(local_app_data)
+        .join("Microsoft")
+        .join("WindowsApps");
+    if !is_windows_store_shim(&windows_apps.join("python.exe")) {
+        return Vec::new();
+    }
+
+    let mut versions: Vec<PythonVersion> = MICROSOFT_STORE_PYTHONS
+        .iter()
+        .filter(|store_python| store_python.family_name.ends_with(STORE_PUBLISHER_ID))
+        .filter(|store_python| {
+            !windows_apps
+                .join(store_python.family_name)
+                .join("python.exe")
+                .is_file()
+        })
+        // All versions are constants, we know they are valid.
+        .map(|store_python| PythonVersion::from_str(store_python.version).unwrap())
+        .collect();
+    versions.sort_unstable_by(|a, b| b.cmp(a));
+    versions.dedup();
+    versions
+}
@@ -37,6 +37,8 @@ pub struct PyVenvConfiguration {
     pub(crate) relocatable: bool,
     /// Was the virtual environment populated with seed packages?
     pub(crate) seed: bool,
+    /// The directory of the base interpreter, from the `home` key.
+    pub(crate) home: Option<PathBuf>,
 }
 
 #[derive(Debug, Error)]
@@ -188,6 +190,7 @@ impl PyVenvConfiguration {
         let mut uv = false;
         let mut relocatable = false;
         let mut seed = false;
+        let mut home = None;
 
         // Per https://snarky.ca/how-virtual-environments-work/, the `pyvenv.cfg` file is not a
         // valid INI file, and is instead expected to be parsed by partitioning each line on the
@@ -211,6 +214,9 @@ impl PyVenvConfiguration {
                 "seed" => {
                     seed = value.trim().to_lowercase() == "true";
                 }
+                "home" => {
+                    home = Some(PathBuf::from(value.trim()));
+                }
                 _ => {}
             }
         }
@@ -220,6 +226,7 @@ impl PyVenvConfiguration {
             uv,
             relocatable,
             seed,
+            home,
         })
     }
 
@@ -242,4 +249,9 @@ impl PyVenvConfiguration {
     pub fn is_seed(&self) -> bool {
         self.seed
     }
+
+    /// Returns the directory of the base interpreter of the virtual environment, if known.
+    pub fn home(&self) -> Option<&Path> {
+        self.home.as_deref()
+    }
 }
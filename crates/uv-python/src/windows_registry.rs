@@ -1,275 +1,7354 @@
 //! PEP 514 interactions with the Windows registry.
 
+use crate::downloads::ManagedPythonDownload;
 use crate::managed::ManagedPythonInstallation;
+use crate::microsoft_store::find_microsoft_store_pythons;
 use crate::platform::Arch;
-use crate::{PythonInstallationKey, PythonVersion, COMPANY_DISPLAY_NAME, COMPANY_KEY};
+use crate::virtualenv::PyVenvConfiguration;
+use crate::{
+    ImplementationName, PythonInstallationKey, PythonSource, PythonVariant, PythonVersion,
+    VersionRequest, COMPANY_DISPLAY_NAME, COMPANY_KEY,
+};
+use backon::{BackoffBuilder, BlockingRetryable};
+use itertools::Itertools;
+#[cfg(test)]
+use std::cell::RefCell;
 use std::cmp::Ordering;
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::ops::ControlFlow;
+use std::os::windows::ffi::OsStrExt;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
 use std::str::FromStr;
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
 use target_lexicon::PointerWidth;
 use thiserror::Error;
-use tracing::debug;
+use tracing::{debug, debug_span, instrument, warn};
+use uv_cache_key::CacheKeyHasher;
+use uv_fs::Simplified;
+use uv_pep440::VersionSpecifier;
+use uv_static::EnvVars;
 use uv_warnings::{warn_user, warn_user_once};
-use windows_registry::{Key, Value, CURRENT_USER, HSTRING, LOCAL_MACHINE};
+use windows_registry::{Key, Type, Value, CURRENT_USER, HSTRING, LOCAL_MACHINE, USERS};
 use windows_result::HRESULT;
-use windows_sys::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+use windows_sys::Win32::Foundation::{
+    CloseHandle, GetLastError, ERROR_ACCESS_DENIED, ERROR_ALREADY_EXISTS, ERROR_BUSY,
+    ERROR_FILE_NOT_FOUND, ERROR_KEY_DELETED, ERROR_LOCK_VIOLATION, ERROR_SHARING_VIOLATION,
+    ERROR_SUCCESS, FILETIME, STILL_ACTIVE,
+};
+use windows_sys::Win32::System::Registry::{
+    RegCreateKeyExW, RegNotifyChangeKeyValue, RegOpenKeyExW, RegQueryInfoKeyW, RegRenameKey,
+    KEY_ALL_ACCESS, KEY_READ, KEY_WOW64_32KEY, KEY_WOW64_64KEY, REG_NOTIFY_CHANGE_LAST_SET,
+    REG_NOTIFY_CHANGE_NAME, REG_OPTION_NON_VOLATILE, REG_SAM_FLAGS,
+};
+use windows_sys::Win32::System::SystemInformation::{
+    IMAGE_FILE_MACHINE_AMD64, IMAGE_FILE_MACHINE_ARM64, IMAGE_FILE_MACHINE_I386,
+};
+use windows_sys::Win32::System::Threading::{
+    GetCurrentProcess, GetExitCodeProcess, IsWow64Process, IsWow64Process2, OpenProcess,
+    PROCESS_QUERY_LIMITED_INFORMATION,
+};
 
 /// Code returned when the registry key doesn't exist.
 const ERROR_NOT_FOUND: HRESULT = HRESULT::from_win32(ERROR_FILE_NOT_FOUND);
 
+/// Code returned when the user isn't allowed to open the registry key.
+const ERROR_DENIED: HRESULT = HRESULT::from_win32(ERROR_ACCESS_DENIED);
+
+/// The maximum length of the name of a registry key, in UTF-16 code units.
+const MAX_KEY_NAME_LENGTH: usize = 255;
+
+/// The registry path below the root keys under which PEP 514 entries are registered.
+const PYTHON_KEY: &str = r"Software\Python";
+
+#[cfg(test)]
+thread_local! {
+    /// A replacement for [`PYTHON_KEY`], so that tests can read and write a sandbox subtree
+    /// instead of the real PEP 514 entries.
+    static PYTHON_KEY_OVERRIDE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// The registry path below the root keys under which PEP 514 entries are registered.
+///
+/// This is always [`PYTHON_KEY`], except in tests using [`with_python_key`].
+fn python_key() -> String {
+    #[cfg(test)]
+    if let Some(python_key) = PYTHON_KEY_OVERRIDE.with_borrow(Clone::clone) {
+        return python_key;
+    }
+    PYTHON_KEY.to_string()
+}
+
+/// Run `f` with [`python_key`] pointing to `python_key` instead of [`PYTHON_KEY`] on the current
+/// thread.
+#[cfg(test)]
+fn with_python_key<T>(python_key: &str, f: impl FnOnce() -> T) -> T {
+    assert!(
+        !python_key.eq_ignore_ascii_case(PYTHON_KEY),
+        "Tests must not use the real PEP 514 registry key"
+    );
+    let previous = PYTHON_KEY_OVERRIDE.replace(Some(python_key.to_string()));
+    let result = f();
+    PYTHON_KEY_OVERRIDE.set(previous);
+    result
+}
+
+/// The name of a tag value listing the runtime prerequisites of an interpreter, such as
+/// `Microsoft.VCRedist.2015+.x64`.
+///
+/// This value isn't part of PEP 514, but used by a few distributions. It can be either a string
+/// with a single requirement or a multi-string value with one requirement per line.
+const RUNTIME_REQUIREMENTS_VALUE: &str = "RuntimeRequirements";
+
+/// The name of an `InstallPath` value with arguments that must be passed to the interpreter,
+/// e.g., `-I` for a distribution that requires isolated mode.
+///
+/// This value isn't part of PEP 514. As a multi-string value, each line is one argument. As a
+/// string value, the arguments are separated by whitespace, without support for quoting.
+const EXECUTABLE_ARGUMENTS_VALUE: &str = "ExecutableArguments";
+
+/// The name of a tag value telling whether the interpreter is a free-threaded build, either as a
+/// DWORD or as a string, `1` or `true` for free-threaded builds.
+///
+/// This value isn't part of PEP 514, see [`read_build_variant`] for the other hints.
+const FREE_THREADED_VALUE: &str = "FreeThreaded";
+
+/// Names of the `InstallPath` values some non-conforming registrations use instead of
+/// `ExecutablePath` for the interpreter, in order of preference.
+const ALTERNATE_EXECUTABLE_VALUES: &[&str] = &["python.exe", "Executable"];
+
+/// The name of the value under our [`COMPANY_KEY`] that records the layout of our entries.
+const REGISTRY_SCHEMA_VALUE: &str = "uv_registry_schema";
+
+/// The version of the registry layout written by this version of uv.
+///
+/// Bump this when changing the set or meaning of the values written by [`write_registry_entry`],
+/// so that readers can tell how to interpret entries written by other uv versions.
+///
+/// * Version 1: The PEP 514 values.
+/// * Version 2: Adds [`VARIANT_VALUE`] and [`BASE_VERSION_VALUE`], and variant suffixes in tags.
+/// * Version 3: Adds the optional [`ALIASES_VALUE`].
+/// * Version 4: Adds the optional [`DESCRIPTION_VALUE`].
+/// * Version 5: Adds [`INSTALL_SOURCE_VALUE`].
+const REGISTRY_SCHEMA_VERSION: u32 = 5;
+
+/// The name of the value of our tags with the [`PythonVariant`] of the installation.
+const VARIANT_VALUE: &str = "Variant";
+
+/// The name of the value of our tags with the version of the installation without the variant,
+/// relating variants, such as `3.13.1t`, to their base version `3.13.1`.
+const BASE_VERSION_VALUE: &str = "BaseVersion";
+
+/// The name of the optional multi-string value of our tags with additional names the installation
+/// can be requested by, such as `latest`.
+const ALIASES_VALUE: &str = "Aliases";
+
+/// The name of the optional value of our tags with a short description of where the installation
+/// comes from, for tools that show more than the `DisplayName`.
+const DESCRIPTION_VALUE: &str = "Description";
+
+/// The name of the value of a tag with the tool that installed the Python, which we write as
+/// [`INSTALL_SOURCE_UV`].
+///
+/// This value isn't part of PEP 514, but other installers may use it the same way.
+const INSTALL_SOURCE_VALUE: &str = "InstallSource";
+
+/// Our [`INSTALL_SOURCE_VALUE`].
+const INSTALL_SOURCE_UV: &str = "uv";
+
+/// The reserved tag below our [`COMPANY_KEY`] that mirrors the entry of the highest installed
+/// version, see [`register_latest_alias`].
+const LATEST_TAG: &str = "Latest";
+
+/// The default `SupportUrl` of our company and tags.
+const SUPPORT_URL: &str = "https://github.com/astral-sh/uv";
+
+/// How to interpret the values of a registry entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RegistryLayout {
+    /// A plain PEP 514 entry from a third party.
+    Pep514,
+    /// An entry written by uv under [`COMPANY_KEY`] with the given schema version.
+    ///
+    /// Entries written before the schema version was introduced have schema version 0.
+    Managed(u32),
+}
+
 /// A Python interpreter found in the Windows registry through PEP 514 or from a known Microsoft
 /// Store path.
 ///
-/// There are a lot more (optional) fields defined in PEP 514, but we only care about path and
-/// version here, for everything else we probe with a Python script.
+/// Besides the executable and the version, this carries the metadata of the registry entry, such
+/// as the architecture, the company and tag, and our own values for uv-managed Pythons. Metadata
+/// that the entry doesn't provide is `None` or empty. The registry is only a hint: Everything
+/// that matters for selecting an interpreter, such as the platform, is still probed with a Python
+/// script.
 #[derive(Debug, Clone)]
-pub(crate) struct WindowsPython {
+pub struct WindowsPython {
     pub(crate) path: PathBuf,
+    /// The installation directory, from the default value of `InstallPath`.
+    pub(crate) prefix: Option<PathBuf>,
+    /// The most specific version of the `SysVersion` and `Version` values of the tag.
+    ///
+    /// `Version` is used if it is a patch version or pre-release of the minor version in
+    /// `SysVersion`, e.g., `3.12.4` for `3.12`, or, for entries registered by uv, if there is no
+    /// `SysVersion`. Otherwise, `SysVersion` is used, since some distributions use `Version` for
+    /// their own release number.
     pub(crate) version: Option<PythonVersion>,
+    /// The `Version` value of the tag, even if [`WindowsPython::version`] is from `SysVersion`.
+    ///
+    /// Some distributions use it for their own release number, see
+    /// [`WindowsPython::display_version`] for the most precise Python version.
+    pub(crate) full_version: Option<PythonVersion>,
+    /// Runtime prerequisites of the interpreter, such as the Visual C++ runtime, from the
+    /// [`RUNTIME_REQUIREMENTS_VALUE`] value.
+    ///
+    /// uv can't install them, but they make for better errors when the interpreter fails to
+    /// launch.
+    pub(crate) runtime_requirements: Vec<String>,
+    /// For uv-managed Pythons, the variant of the interpreter, e.g., free-threaded.
+    pub(crate) variant: Option<PythonVariant>,
+    /// For uv-managed Pythons, the version the variant was built from, such that, e.g., `3.13.1`
+    /// and `3.13.1t` can be grouped together.
+    pub(crate) base_version: Option<PythonVersion>,
+    /// For uv-managed Pythons, the SHA256 of the archive the interpreter was installed from.
+    pub(crate) download_sha256: Option<String>,
+    /// The architecture the interpreter was built for, if the entry allows telling, see
+    /// [`read_arch_hint`].
+    pub(crate) arch: Option<Arch>,
+    /// The pointer width of the interpreter, from `SysArchitecture` or, as a fallback, from the
+    /// registry view, the tag or the `DisplayName`, see [`read_pointer_width`].
+    pub(crate) pointer_width: Option<PointerWidth>,
+    /// The GUI executable, such as `pythonw.exe`, from `WindowedExecutablePath`, if it differs from
+    /// the console executable.
+    pub(crate) windowed_path: Option<PathBuf>,
+    /// Arguments to pass to the interpreter before any other arguments, from the
+    /// [`EXECUTABLE_ARGUMENTS_VALUE`] value. Usually empty.
+    pub(crate) executable_arguments: Vec<String>,
+    /// For uv-managed Pythons, additional names the interpreter can be requested by, from the
+    /// [`ALIASES_VALUE`] value.
+    pub(crate) aliases: Vec<String>,
+    /// The last time the tag key or one of its values was written, usually when the Python was
+    /// installed or updated.
+    pub(crate) last_write_time: Option<SystemTime>,
+    /// The root the interpreter is registered under, `None` for Pythons not from the registry.
+    pub(crate) root: Option<RegistryRoot>,
+    /// The company key of the registry entry, e.g., `PythonCore`.
+    pub(crate) company: Option<String>,
+    /// The tag key of the registry entry, e.g., `3.12`.
+    pub(crate) tag: Option<String>,
+    /// The `DisplayName` of the tag, e.g., `Python 3.12 (64-bit)`.
+    ///
+    /// Different companies may use the same name, see [`duplicate_display_names`].
+    pub(crate) display_name: Option<String>,
+    /// The layout of the registry entry, `None` for Pythons not from the registry, see
+    /// [`WindowsPython::is_managed`].
+    pub(crate) layout: Option<RegistryLayout>,
+    /// Where the Python comes from, from the [`INSTALL_SOURCE_VALUE`] of the tag, e.g., `uv`, or
+    /// the `DisplayName` of the company, e.g., `Python Software Foundation`.
+    pub(crate) install_source: Option<String>,
+    /// How an administrator designated the interpreter as the default of the machine, if they did.
+    pub(crate) default_designation: Option<DefaultDesignation>,
+    /// The variant of the build, see [`read_build_variant`].
+    ///
+    /// Unlike [`WindowsPython::variant`], this is also known for Pythons not installed by uv.
+    pub(crate) build_variant: PythonVariant,
+    /// The executable with symlinks and junctions resolved, if requested with
+    /// [`RegistryScanOptions::with_resolve_executables`].
+    pub(crate) real_path: Option<PathBuf>,
+    /// The version reported by the interpreter, see [`WindowsPython::version_or_infer`].
+    inferred_version: OnceLock<PythonVersion>,
 }
 
-/// Find all Pythons registered in the Windows registry following PEP 514.
-pub(crate) fn registry_pythons() -> Result<Vec<WindowsPython>, windows_result::Error> {
-    let mut registry_pythons = Vec::new();
-    // Prefer `HKEY_CURRENT_USER` over `HKEY_LOCAL_MACHINE`
-    for root_key in [CURRENT_USER, LOCAL_MACHINE] {
-        let Ok(key_python) = root_key.open(r"Software\Python") else {
-            continue;
+impl WindowsPython {
+    /// A Python without any of the optional metadata.
+    pub(crate) fn new(path: PathBuf, version: Option<PythonVersion>) -> Self {
+        Self {
+            path,
+            prefix: None,
+            version,
+            full_version: None,
+            runtime_requirements: Vec::new(),
+            variant: None,
+            base_version: None,
+            download_sha256: None,
+            arch: None,
+            pointer_width: None,
+            windowed_path: None,
+            executable_arguments: Vec::new(),
+            aliases: Vec::new(),
+            last_write_time: None,
+            root: None,
+            company: None,
+            tag: None,
+            layout: None,
+            display_name: None,
+            install_source: None,
+            default_designation: None,
+            build_variant: PythonVariant::Default,
+            real_path: None,
+            inferred_version: OnceLock::new(),
+        }
+    }
+
+    /// The executable, from `ExecutablePath`.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The installation directory, from the default value of `InstallPath`.
+    pub fn prefix(&self) -> Option<&Path> {
+        self.prefix.as_deref()
+    }
+
+    /// The most specific version of the `SysVersion` and `Version` values of the tag.
+    ///
+    /// `Version` is used if it is a patch version or pre-release of the minor version in
+    /// `SysVersion`, e.g., `3.12.4` for `3.12`, or, for entries registered by uv, if there is no
+    /// `SysVersion`. Otherwise, `SysVersion` is used, since some distributions use `Version` for
+    /// their own release number.
+    pub fn version(&self) -> Option<&PythonVersion> {
+        self.version.as_ref()
+    }
+
+    /// The `Version` value of the tag, even if [`WindowsPython::version`] is from `SysVersion`.
+    pub fn full_version(&self) -> Option<&PythonVersion> {
+        self.full_version.as_ref()
+    }
+
+    /// Runtime prerequisites of the interpreter, such as the Visual C++ runtime.
+    pub fn runtime_requirements(&self) -> &[String] {
+        &self.runtime_requirements
+    }
+
+    /// For uv-managed Pythons, the variant of the interpreter, e.g., free-threaded.
+    pub fn variant(&self) -> Option<PythonVariant> {
+        self.variant
+    }
+
+    /// For uv-managed Pythons, the version the variant was built from.
+    pub fn base_version(&self) -> Option<&PythonVersion> {
+        self.base_version.as_ref()
+    }
+
+    /// For uv-managed Pythons, the SHA256 of the archive the interpreter was installed from.
+    pub fn download_sha256(&self) -> Option<&str> {
+        self.download_sha256.as_deref()
+    }
+
+    /// The architecture the interpreter was built for, if the entry allows telling.
+    pub fn arch(&self) -> Option<Arch> {
+        self.arch
+    }
+
+    /// The pointer width of the interpreter.
+    pub fn pointer_width(&self) -> Option<PointerWidth> {
+        self.pointer_width
+    }
+
+    /// The GUI executable, such as `pythonw.exe`, if it differs from the console executable.
+    pub fn windowed_path(&self) -> Option<&Path> {
+        self.windowed_path.as_deref()
+    }
+
+    /// Arguments to pass to the interpreter before any other arguments. Usually empty.
+    pub fn executable_arguments(&self) -> &[String] {
+        &self.executable_arguments
+    }
+
+    /// For uv-managed Pythons, additional names the interpreter can be requested by.
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    /// The last time the tag key or one of its values was written.
+    pub fn last_write_time(&self) -> Option<SystemTime> {
+        self.last_write_time
+    }
+
+    /// The root the interpreter is registered under, `None` for Pythons not from the registry.
+    pub fn root(&self) -> Option<&RegistryRoot> {
+        self.root.as_ref()
+    }
+
+    /// The company key of the registry entry, e.g., `PythonCore`.
+    pub fn company(&self) -> Option<&str> {
+        self.company.as_deref()
+    }
+
+    /// The tag key of the registry entry, e.g., `3.12`.
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    /// The `DisplayName` of the tag, e.g., `Python 3.12 (64-bit)`.
+    pub fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref()
+    }
+
+    /// Whether the entry was registered by uv, i.e., below our [`COMPANY_KEY`].
+    pub fn is_managed(&self) -> bool {
+        matches!(self.layout, Some(RegistryLayout::Managed(_)))
+    }
+
+    /// Where the Python comes from, e.g., `uv` or `Python Software Foundation`.
+    pub fn install_source(&self) -> Option<&str> {
+        self.install_source.as_deref()
+    }
+
+    /// How an administrator designated the interpreter as the default of the machine, if they did.
+    pub fn default_designation(&self) -> Option<DefaultDesignation> {
+        self.default_designation
+    }
+
+    /// Whether an administrator designated the interpreter as the default of the machine, see
+    /// [`WindowsPython::default_designation`].
+    pub fn is_default(&self) -> bool {
+        self.default_designation.is_some()
+    }
+
+    /// Whether the interpreter is a Python 2, which uv doesn't support, from the major version of
+    /// [`WindowsPython::version`].
+    ///
+    /// Python 2 entries are only included with [`RegistryScanOptions::with_include_python2`]. They
+    /// must not be selected for operations that require Python 3.
+    pub fn is_legacy(&self) -> bool {
+        is_legacy_version(self.version.as_ref())
+    }
+
+    /// The variant of the build, e.g., free-threaded, also for Pythons not installed by uv.
+    pub fn build_variant(&self) -> PythonVariant {
+        self.build_variant
+    }
+
+    /// Whether the interpreter is a free-threaded build, see [`WindowsPython::build_variant`].
+    pub fn is_free_threaded(&self) -> bool {
+        self.build_variant == PythonVariant::Freethreaded
+    }
+
+    /// The executable with symlinks and junctions resolved, if requested with
+    /// [`RegistryScanOptions::with_resolve_executables`].
+    pub fn real_path(&self) -> Option<&Path> {
+        self.real_path.as_deref()
+    }
+
+    /// The registered version or, if the entry doesn't have a version, the version reported by
+    /// running the interpreter with `--version`.
+    ///
+    /// The interpreter is only run once per entry, later calls reuse the result.
+    pub fn version_or_infer(&self) -> io::Result<PythonVersion> {
+        if let Some(version) = &self.version {
+            return Ok(version.clone());
+        }
+        if let Some(version) = self.inferred_version.get() {
+            return Ok(version.clone());
+        }
+        debug!(
+            "Registry entry of `{}` has no version, running the interpreter to determine it",
+            self.path.display()
+        );
+        let output = Command::new(&self.path).arg("--version").output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "`{} --version` failed with {}",
+                self.path.display(),
+                output.status
+            )));
+        }
+        // Python 2 prints the version to stderr.
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let report = if stdout.trim().is_empty() {
+            stderr
+        } else {
+            stdout
         };
-        for company in key_python.keys()? {
-            // Reserved name according to the PEP.
-            if company == "PyLauncher" {
-                continue;
+        // Ex) `Python 3.12.4`, or for PyPy, `Python 3.10.14 (...)` followed by the PyPy version
+        let version = report
+            .lines()
+            .next()
+            .and_then(|line| line.strip_prefix("Python "))
+            .and_then(|line| line.split_whitespace().next())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Unexpected output of `{} --version`: {report}",
+                        self.path.display()
+                    ),
+                )
+            })?;
+        let version = PythonVersion::from_str(version)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(self.inferred_version.get_or_init(|| version).clone())
+    }
+
+    /// Convert into a candidate for Python discovery, keeping the metadata the registry knows
+    /// before the interpreter is queried, see [`RegistryCandidate`].
+    pub(crate) fn into_discovery_candidate(self, source: PythonSource) -> RegistryCandidate {
+        RegistryCandidate {
+            implementation: self.implementation(),
+            source,
+            path: self.path,
+            version: self.version,
+            arch: self.arch,
+            pointer_width: self.pointer_width,
+        }
+    }
+
+    /// Whether the interpreter runs natively on this machine, or emulated, such as an x64 Python
+    /// on Windows on ARM. `None` if the architecture of the interpreter or the machine is
+    /// unknown.
+    pub fn is_native(&self) -> Option<bool> {
+        Some(self.arch?.family() == native_arch()?.family())
+    }
+
+    /// A note for interpreters built for another architecture than the machine, e.g., `32-bit,
+    /// host is 64-bit`, for explaining why, e.g., a build expecting a 64-bit Python fails.
+    ///
+    /// This is informational only, such interpreters are still discovered. `None` if the
+    /// architectures match or are unknown.
+    pub fn host_arch_note(&self) -> Option<String> {
+        arch_mismatch_note(self.pointer_width, self.arch, native_arch()?)
+    }
+
+    /// The implementation of the interpreter, if the company is a [`KnownDistribution`].
+    ///
+    /// For uv-managed Pythons, the implementation is the prefix of the tag, e.g., `CPython3.13.1`.
+    pub fn implementation(&self) -> Option<ImplementationName> {
+        match known_distribution(self.company.as_deref()?)? {
+            KnownDistribution::Uv => {
+                let tag = self.tag.as_deref()?;
+                ImplementationName::iter_all()
+                    .find(|implementation| tag.starts_with(implementation.pretty()))
             }
-            let Ok(company_key) = key_python.open(&company) else {
-                // Ignore invalid entries
-                continue;
-            };
-            for tag in company_key.keys()? {
-                let tag_key = company_key.open(&tag)?;
+            distribution => distribution.implementation(),
+        }
+    }
 
-                if let Some(registry_python) = read_registry_entry(&company, &tag, &tag_key) {
-                    registry_pythons.push(registry_python);
-                }
+    /// If the registered interpreter is a virtual environment, the executable of its base
+    /// interpreter, from the `home` key of its `pyvenv.cfg`.
+    ///
+    /// The `pyvenv.cfg` is looked up next to the executable, in the parent directory for the
+    /// `Scripts` layout of virtual environments on Windows, and in the [`WindowsPython::prefix`].
+    pub fn venv_base_executable(&self) -> Option<PathBuf> {
+        let executable_dir = self.path.parent()?;
+        let cfg = [
+            Some(executable_dir),
+            executable_dir.parent(),
+            self.prefix.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|dir| dir.join("pyvenv.cfg"))
+        .find(|cfg| cfg.is_file())?;
+        let config = match PyVenvConfiguration::parse(&cfg) {
+            Ok(config) => config,
+            Err(err) => {
+                debug!("Failed to read `{}`: {err}", cfg.user_display());
+                return None;
+            }
+        };
+        Some(config.home()?.join(self.path.file_name()?))
+    }
+
+    /// The GUI executable next to the console executable by convention, e.g., `pythonw.exe` for
+    /// `python.exe`, for entries without a [`WindowsPython::windowed_path`].
+    ///
+    /// This ignores [`WindowsPython::windowed_path`], so callers can prefer the registered
+    /// executable. `None` if no such executable exists.
+    pub fn infer_windowed_executable(&self) -> Option<PathBuf> {
+        let executable_dir = self.path.parent()?;
+        // Ex) `pypy3.exe` -> `pypy3w.exe`
+        let sibling = self
+            .path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(|stem| format!("{stem}w.exe"));
+        sibling
+            .into_iter()
+            .chain(std::iter::once("pythonw.exe".to_string()))
+            .map(|name| executable_dir.join(name))
+            .find(|windowed| windowed.is_file())
+    }
+
+    /// Whether the interpreter was registered with the alias, ignoring case.
+    pub fn has_alias(&self, alias: &str) -> bool {
+        self.aliases
+            .iter()
+            .any(|registered| registered.eq_ignore_ascii_case(alias))
+    }
+
+    /// The most precise version for showing to users, e.g., `3.12.4` for an entry with `SysVersion`
+    /// `3.12` and `Version` `3.12.4`.
+    ///
+    /// This is [`WindowsPython::full_version`] if it's a version of the same minor version as
+    /// [`WindowsPython::version`], which remains the version for matching requests.
+    pub fn display_version(&self) -> Option<&PythonVersion> {
+        match (&self.version, &self.full_version) {
+            (Some(version), Some(full_version))
+                if (full_version.major(), full_version.minor())
+                    == (version.major(), version.minor()) =>
+            {
+                Some(full_version)
             }
+            (version, _) => version.as_ref(),
         }
     }
 
-    // The registry has no natural ordering, so we're processing the latest version first.
-    registry_pythons.sort_by(|a, b| {
-        match (&a.version, &b.version) {
-            // Place entries with a version before those without a version.
-            (Some(_), None) => Ordering::Greater,
-            (None, Some(_)) => Ordering::Less,
-            // We want the highest version on top, which is the inverse from the regular order. The
-            // path is an arbitrary but stable tie-breaker.
-            (Some(version_a), Some(version_b)) => {
-                version_a.cmp(version_b).reverse().then(a.path.cmp(&b.path))
+    /// The `DisplayName` of the tag, followed by the company if the name is one of the
+    /// `duplicates` returned by [`duplicate_display_names`], e.g.,
+    /// `Python 3.12 (64-bit) (PythonCore)`.
+    pub fn unambiguous_display_name(&self, duplicates: &HashSet<String>) -> Option<String> {
+        let display_name = self.display_name.as_ref()?;
+        match &self.company {
+            Some(company) if duplicates.contains(display_name) => {
+                Some(format!("{display_name} ({company})"))
             }
-            // Sort the entries without a version arbitrarily, but stable (by path).
-            (None, None) => a.path.cmp(&b.path),
+            _ => Some(display_name.clone()),
         }
-    });
+    }
 
-    Ok(registry_pythons)
-}
+    /// The version in the `x.y.z` format of [`PythonInstallationKey::sys_version`], for comparing
+    /// the entry with the key of a managed installation.
+    ///
+    /// `None` if the architecture or the patch version of [`WindowsPython::display_version`] is
+    /// unknown, since the entry can't be compared to a key then.
+    pub fn sys_version(&self) -> Option<String> {
+        self.arch?;
+        let version = self.display_version()?;
+        Some(format!(
+            "{}.{}.{}",
+            version.major(),
+            version.minor(),
+            version.patch()?
+        ))
+    }
 
-fn read_registry_entry(company: &str, tag: &str, tag_key: &Key) -> Option<WindowsPython> {
-    // `ExecutablePath` is mandatory for executable Pythons.
-    let Ok(executable_path) = tag_key
-        .open("InstallPath")
-        .and_then(|install_path| install_path.get_value("ExecutablePath"))
-        .and_then(String::try_from)
-    else {
-        debug!(
-            r"Python interpreter in the registry is not executable: `Software\Python\{}\{}",
-            company, tag
-        );
-        return None;
-    };
+    /// The major and minor version of the interpreter, if the entry has a version.
+    pub fn major_minor(&self) -> Option<(u8, u8)> {
+        let version = self.version.as_ref()?;
+        Some((version.major(), version.minor()))
+    }
 
-    // `SysVersion` is optional.
-    let version = tag_key
-        .get_value("SysVersion")
-        .and_then(String::try_from)
-        .ok()
-        .and_then(|s| match PythonVersion::from_str(&s) {
-            Ok(version) => Some(version),
-            Err(err) => {
-                debug!(
-                    "Skipping Python interpreter ({executable_path}) \
-                    with invalid registry version {s}: {err}",
-                );
-                None
+    /// Whether the registered version matches the request.
+    ///
+    /// Pre-releases, such as `3.13.0rc1`, only match if `allow_prereleases` is set or the request
+    /// explicitly asks for a pre-release, e.g., `3.13rc1`. Entries with only a major version match
+    /// all requests for that major version, entries without a version only match unconstrained
+    /// requests.
+    pub fn matches_version_request(
+        &self,
+        request: &VersionRequest,
+        allow_prereleases: bool,
+    ) -> bool {
+        let Some(version) = &self.version else {
+            return matches!(request, VersionRequest::Any | VersionRequest::Default);
+        };
+        // With only a major version, such as `3`, the minor version is unknown, so any request
+        // for the major version could match.
+        if version.release().len() == 1 {
+            return match request {
+                VersionRequest::Any | VersionRequest::Default | VersionRequest::Range(..) => true,
+                VersionRequest::Major(major, _)
+                | VersionRequest::MajorMinor(major, ..)
+                | VersionRequest::MajorMinorPatch(major, ..)
+                | VersionRequest::MajorMinorPrerelease(major, ..) => *major == version.major(),
+            };
+        }
+        if version.pre().is_some() && !allow_prereleases {
+            let requests_prerelease = match request {
+                VersionRequest::MajorMinorPrerelease(..) => true,
+                VersionRequest::Range(specifiers, _) => {
+                    specifiers.iter().any(VersionSpecifier::any_prerelease)
+                }
+                _ => false,
+            };
+            if !requests_prerelease {
+                return false;
             }
-        });
-
-    Some(WindowsPython {
-        path: PathBuf::from(executable_path),
-        version,
-    })
+        }
+        // Some distributions omit the patch version (example: `SysVersion: 3.9`)
+        if version.string.chars().filter(|c| *c == '.').count() == 1 {
+            request.matches_major_minor(version.major(), version.minor())
+        } else {
+            request.matches_version(version)
+        }
+    }
 }
 
-#[derive(Debug, Error)]
-pub enum ManagedPep514Error {
-    #[error("Windows has an unknown pointer width for arch: `{_0}`")]
-    InvalidPointerSize(Arch),
+/// A Python from the registry or the Microsoft Store as a candidate for discovery, see
+/// [`WindowsPython::into_discovery_candidate`].
+///
+/// The fields are what the registry claims about the interpreter, which lets discovery skip
+/// incompatible interpreters without running them. Each is `None` if the entry doesn't tell.
+/// Everything else, such as the platform, `sys.prefix`, the variant of non-uv Pythons, or whether
+/// the interpreter is a virtual environment, is only known from querying the interpreter, which
+/// remains authoritative.
+#[derive(Debug, Clone)]
+pub struct RegistryCandidate {
+    source: PythonSource,
+    path: PathBuf,
+    version: Option<PythonVersion>,
+    arch: Option<Arch>,
+    pointer_width: Option<PointerWidth>,
+    implementation: Option<ImplementationName>,
 }
 
-/// Register a managed Python installation in the Windows registry following PEP 514.
-pub fn create_registry_entry(
-    installation: &ManagedPythonInstallation,
-    errors: &mut Vec<(PythonInstallationKey, anyhow::Error)>,
-) -> Result<(), ManagedPep514Error> {
-    let pointer_width = match installation.key().arch().family().pointer_width() {
-        Ok(PointerWidth::U32) => 32,
-        Ok(PointerWidth::U64) => 64,
-        _ => {
-            return Err(ManagedPep514Error::InvalidPointerSize(
-                *installation.key().arch(),
-            ));
-        }
-    };
+impl RegistryCandidate {
+    /// Where the Python was found.
+    pub fn source(&self) -> PythonSource {
+        self.source
+    }
 
-    if let Err(err) = write_registry_entry(installation, pointer_width) {
-        errors.push((installation.key().clone(), err.into()));
+    /// The executable of the interpreter.
+    pub fn path(&self) -> &Path {
+        &self.path
     }
 
-    Ok(())
-}
+    /// The registered version, see [`WindowsPython::version`].
+    pub fn version(&self) -> Option<&PythonVersion> {
+        self.version.as_ref()
+    }
 
-fn write_registry_entry(
-    installation: &ManagedPythonInstallation,
-    pointer_width: i32,
-) -> windows_registry::Result<()> {
-    // We currently just overwrite all known keys, without removing prior entries first
+    /// The architecture hinted by the entry, see [`read_arch_hint`].
+    pub fn arch(&self) -> Option<Arch> {
+        self.arch
+    }
 
-    // Similar to using the bin directory in HOME on Unix, we only install for the current user
-    // on Windows.
-    let company = CURRENT_USER.create(format!("Software\\Python\\{COMPANY_KEY}"))?;
-    company.set_string("DisplayName", COMPANY_DISPLAY_NAME)?;
-    company.set_string("SupportUrl", "https://github.com/astral-sh/uv")?;
+    /// The pointer width of the interpreter, see [`read_pointer_width`].
+    pub fn pointer_width(&self) -> Option<PointerWidth> {
+        self.pointer_width
+    }
 
-    // Ex) CPython3.13.1
-    let tag = company.create(registry_python_tag(installation.key()))?;
-    let display_name = format!(
-        "{} {} ({}-bit)",
-        installation.key().implementation().pretty(),
-        installation.key().version(),
-        pointer_width
-    );
-    tag.set_string("DisplayName", &display_name)?;
-    tag.set_string("SupportUrl", "https://github.com/astral-sh/uv")?;
-    tag.set_string("Version", &installation.key().version().to_string())?;
-    tag.set_string("SysVersion", &installation.key().sys_version())?;
-    tag.set_string("SysArchitecture", &format!("{pointer_width}bit"))?;
-    // Store `python-build-standalone` release
-    if let Some(url) = installation.url() {
-        tag.set_string("DownloadUrl", url)?;
+    /// The implementation guessed from the company, see [`WindowsPython::implementation`].
+    pub fn implementation(&self) -> Option<ImplementationName> {
+        self.implementation
     }
-    if let Some(sha256) = installation.sha256() {
-        tag.set_string("DownloadSha256", sha256)?;
+}
+
+/// Discovery yields the source and the executable of each candidate, like for all other sources.
+impl From<RegistryCandidate> for (PythonSource, PathBuf) {
+    fn from(candidate: RegistryCandidate) -> Self {
+        (candidate.source, candidate.path)
     }
+}
 
-    let install_path = tag.create("InstallPath")?;
-    install_path.set_value(
-        "",
-        &Value::from(&HSTRING::from(installation.path().as_os_str())),
-    )?;
-    install_path.set_value(
-        "ExecutablePath",
-        &Value::from(&HSTRING::from(installation.executable(false).as_os_str())),
-    )?;
-    install_path.set_value(
-        "WindowedExecutablePath",
-        &Value::from(&HSTRING::from(installation.executable(true).as_os_str())),
-    )?;
-    Ok(())
+impl std::fmt::Display for WindowsPython {
+    /// Ex) `CPython 3.12.4 (64-bit) - C:\Python312\python.exe [HKCU]`, omitting unknown parts.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(
+            self.implementation()
+                .map_or("Python", ImplementationName::pretty),
+        )?;
+        if let Some(version) = self.display_version() {
+            write!(f, " {version}")?;
+        }
+        if let Some(pointer_width) = self.pointer_width {
+            write!(f, " ({}-bit)", pointer_width.bits())?;
+        }
+        write!(f, " - {}", self.path.display())?;
+        if let Some(root) = &self.root {
+            write!(f, " [{root}]")?;
+        }
+        Ok(())
+    }
 }
 
-fn registry_python_tag(key: &PythonInstallationKey) -> String {
-    format!("{}{}", key.implementation().pretty(), key.version())
+/// A root key of the registry containing PEP 514 entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryRoot {
+    /// `HKEY_CURRENT_USER`, for per-user installations, including uv-managed Pythons.
+    CurrentUser,
+    /// `HKEY_LOCAL_MACHINE`, for machine-wide installations.
+    LocalMachine,
+    /// A user hive below `HKEY_USERS`, e.g., the hive of the default user profile loaded with
+    /// `reg load HKU\DefaultUser C:\Users\Default\NTUSER.DAT`, for registering Pythons for
+    /// another user when provisioning a machine.
+    Users(String),
 }
 
-/// Remove requested Python entries from the Windows Registry (PEP 514).
-pub fn remove_registry_entry<'a>(
-    installations: impl IntoIterator<Item = &'a ManagedPythonInstallation>,
-    all: bool,
-    errors: &mut Vec<(PythonInstallationKey, anyhow::Error)>,
-) {
-    let astral_key = format!("Software\\Python\\{COMPANY_KEY}");
-    if all {
-        debug!("Removing registry key HKCU:\\{}", astral_key);
-        if let Err(err) = CURRENT_USER.remove_tree(&astral_key) {
-            if err.code() == ERROR_NOT_FOUND {
-                debug!("No registry entries to remove, no registry key {astral_key}");
-            } else {
-                warn_user!("Failed to clear registry entries under {astral_key}: {err}");
-            }
+impl RegistryRoot {
+    /// Open a key below this root in one of the views returned by [`RegistryRoot::open_views`].
+    fn open_view(&self, view: RegistryView, path: &str) -> windows_result::Result<Key> {
+        match (self, view) {
+            (Self::LocalMachine, RegistryView::Wow64) => open_wow64_view(LOCAL_MACHINE, path),
+            (Self::LocalMachine, _) => open_native_view(LOCAL_MACHINE, path),
+            (Self::CurrentUser, _) => CURRENT_USER.open(path),
+            (Self::Users(hive), _) => USERS.open(format!("{hive}\\{path}")),
         }
-        return;
     }
 
-    for installation in installations {
-        let python_tag = registry_python_tag(installation.key());
-        let python_entry = format!("{astral_key}\\{python_tag}");
-        debug!("Removing registry key HKCU:\\{}", python_entry);
-        if let Err(err) = CURRENT_USER.remove_tree(&python_entry) {
-            if err.code() == ERROR_NOT_FOUND {
-                debug!(
-                    "No registry entries to remove for {}, no registry key {}",
-                    installation.key(),
-                    python_entry
-                );
-            } else {
-                errors.push((
-                    installation.key().clone(),
-                    anyhow::Error::new(err)
-                        .context("Failed to clear registry entries under HKCU:\\{python_entry}"),
-                ));
+    /// Open the PEP 514 key below this root in each registry view with separate entries.
+    fn open_python_views(&self) -> Vec<Key> {
+        self.open_views(&python_key())
+            .into_iter()
+            .map(|(_, key)| key)
+            .collect()
+    }
+
+    /// Open a key below this root in each registry view with separate entries.
+    ///
+    /// On 64-bit Windows, `HKEY_LOCAL_MACHINE\Software` has a separate 32-bit view
+    /// (`WOW6432Node`), in which 32-bit installers register machine-wide Pythons. The user keys
+    /// are shared between the views, so they are only opened once.
+    fn open_views(&self, path: &str) -> Vec<(RegistryView, Key)> {
+        match self {
+            Self::LocalMachine => {
+                let mut views: Vec<(RegistryView, Key)> = open_native_view(LOCAL_MACHINE, path)
+                    .into_iter()
+                    .map(|key| (RegistryView::Native, key))
+                    .collect();
+                // On 32-bit Windows, there is only one view.
+                if cfg!(target_pointer_width = "64") || is_wow64_process() {
+                    views.extend(
+                        open_wow64_view(LOCAL_MACHINE, path).map(|key| (RegistryView::Wow64, key)),
+                    );
+                }
+                views
             }
-        };
+            Self::CurrentUser => CURRENT_USER
+                .open(path)
+                .into_iter()
+                .map(|key| (RegistryView::Shared, key))
+                .collect(),
+            Self::Users(hive) => USERS
+                .open(format!("{hive}\\{path}"))
+                .into_iter()
+                .map(|key| (RegistryView::Shared, key))
+                .collect(),
+        }
     }
-}
 
-/// Remove Python entries from the Windows Registry (PEP 514) that are not matching any
-/// installation.
-pub fn remove_orphan_registry_entries(installations: &[ManagedPythonInstallation]) {
-    let keep: HashSet<_> = installations
-        .iter()
-        .map(|installation| registry_python_tag(installation.key()))
-        .collect();
-    let astral_key = format!("Software\\Python\\{COMPANY_KEY}");
-    let key = match CURRENT_USER.open(&astral_key) {
-        Ok(subkeys) => subkeys,
-        Err(err) if err.code() == ERROR_NOT_FOUND => {
-            return;
+    /// Create a key below the PEP 514 key of this root, e.g., for our company.
+    fn create_below_python_key(&self, path: &str) -> windows_result::Result<Key> {
+        match self {
+            Self::CurrentUser => CURRENT_USER.create(format!("{}\\{path}", python_key())),
+            Self::LocalMachine => LOCAL_MACHINE.create(format!("{}\\{path}", python_key())),
+            Self::Users(hive) => USERS.create(format!("{hive}\\{}\\{path}", python_key())),
         }
-        Err(err) => {
-            // TODO(konsti): We don't have an installation key here.
-            warn_user_once!("Failed to open HKCU:\\{astral_key}: {err}");
-            return;
+    }
+
+    /// Like [`RegistryRoot::create_below_python_key`], but in the registry view of an interpreter
+    /// with the given pointer width, independent of the bitness of the process.
+    ///
+    /// Like the python.org installers, 64-bit Pythons are registered in the 64-bit view and 32-bit
+    /// Pythons in `WOW6432Node`. Without this, a 32-bit uv would register 64-bit Pythons in
+    /// `WOW6432Node` of `HKEY_LOCAL_MACHINE`. The user keys are shared between the views. Keys
+    /// created below the returned key remain in its view.
+    fn create_below_python_key_in_view(
+        &self,
+        path: &str,
+        pointer_width: i32,
+    ) -> windows_result::Result<Key> {
+        match self {
+            Self::LocalMachine => create_key_with_access(
+                LOCAL_MACHINE,
+                &format!("{}\\{path}", python_key()),
+                KEY_ALL_ACCESS | registry_view_access(pointer_width),
+            ),
+            Self::CurrentUser | Self::Users(_) => self.create_below_python_key(path),
         }
-    };
-    // Separate assignment since `keys()` creates a borrow.
-    let subkeys = match key.keys() {
-        Ok(subkeys) => subkeys,
+    }
+}
+
+/// The registry view a key was opened in, see [`RegistryRoot::open_views`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RegistryView {
+    /// The key is shared between the views, like the keys of `HKEY_CURRENT_USER\Software`.
+    Shared,
+    /// The native view, e.g., the 64-bit view on 64-bit Windows.
+    Native,
+    /// The 32-bit view (`WOW6432Node`) on 64-bit Windows.
+    Wow64,
+}
+
+impl RegistryView {
+    /// The pointer width of the interpreters registered in this view.
+    ///
+    /// Windows redirects the registry writes of 32-bit installers to the 32-bit view, so entries
+    /// in a separate view are usually for interpreters of its bitness.
+    fn pointer_width(self) -> Option<PointerWidth> {
+        match self {
+            Self::Shared => None,
+            Self::Native => native_arch()?.family().pointer_width().ok(),
+            Self::Wow64 => Some(PointerWidth::U32),
+        }
+    }
+}
+
+impl std::fmt::Display for RegistryRoot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CurrentUser => f.write_str("HKCU"),
+            Self::LocalMachine => f.write_str("HKLM"),
+            Self::Users(hive) => write!(f, "HKU\\{hive}"),
+        }
+    }
+}
+
+/// Options for scanning the registry with [`registry_pythons_with`].
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct RegistryScanOptions {
+    /// The root keys to scan, in order of preference.
+    roots: Vec<RegistryRoot>,
+    /// If non-empty, only scan these companies.
+    allowed_companies: Vec<String>,
+    /// Skip these companies.
+    denied_companies: Vec<String>,
+    /// Skip Pythons with a version lower than this one. Pythons without a version are kept.
+    min_version: Option<PythonVersion>,
+    /// If non-empty, only include Pythons of these implementations. Pythons of an unknown
+    /// implementation are kept.
+    implementations: Vec<ImplementationName>,
+    /// Skip Pythons without a readable version instead of returning them for probing.
+    strict: bool,
+    /// Include Pythons whose executable doesn't exist.
+    include_broken: bool,
+    /// Stop scanning after this duration and return the Pythons found so far.
+    timeout: Option<Duration>,
+    /// Include Python 2 installations, which uv doesn't support.
+    include_python2: bool,
+    /// Resolve the executables to their real path and skip entries with the same real path.
+    resolve_executables: bool,
+    /// The order of the returned Pythons.
+    sort_order: RegistrySortOrder,
+    /// Keys below the roots to scan in addition to the PEP 514 key, with the same layout.
+    additional_base_paths: Vec<String>,
+    /// Log a summary of the skipped entries instead of each skipped entry.
+    summarize_skipped: bool,
+    /// Stop scanning once this flag is set.
+    cancelled: Option<Arc<AtomicBool>>,
+    /// Only include Pythons built for the architecture of the machine.
+    native_only: bool,
+}
+
+/// The order of the Pythons returned by [`registry_pythons_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RegistrySortOrder {
+    /// The highest version first.
+    #[default]
+    Version,
+    /// The most recently registered first, see [`WindowsPython::last_write_time`], with the
+    /// version as tie-breaker.
+    Recency,
+}
+
+impl Default for RegistryScanOptions {
+    fn default() -> Self {
+        Self {
+            // Prefer `HKEY_CURRENT_USER` over `HKEY_LOCAL_MACHINE`
+            roots: vec![RegistryRoot::CurrentUser, RegistryRoot::LocalMachine],
+            allowed_companies: Vec::new(),
+            denied_companies: Vec::new(),
+            min_version: None,
+            implementations: Vec::new(),
+            strict: false,
+            include_broken: true,
+            timeout: None,
+            include_python2: false,
+            resolve_executables: false,
+            sort_order: RegistrySortOrder::default(),
+            additional_base_paths: Vec::new(),
+            summarize_skipped: false,
+            cancelled: None,
+            native_only: false,
+        }
+    }
+}
+
+impl RegistryScanOptions {
+    /// The root keys to scan, in order of preference, by default `HKEY_CURRENT_USER` before
+    /// `HKEY_LOCAL_MACHINE`.
+    #[must_use]
+    pub fn with_roots(self, roots: Vec<RegistryRoot>) -> Self {
+        Self { roots, ..self }
+    }
+
+    /// Only scan the entries of these companies, compared case-insensitively. All companies are
+    /// scanned if the list is empty, the default.
+    #[must_use]
+    pub fn with_allowed_companies(self, allowed_companies: Vec<String>) -> Self {
+        Self {
+            allowed_companies,
+            ..self
+        }
+    }
+
+    /// Skip the entries of these companies, compared case-insensitively, even if they are
+    /// allowed with [`RegistryScanOptions::with_allowed_companies`].
+    #[must_use]
+    pub fn with_denied_companies(self, denied_companies: Vec<String>) -> Self {
+        Self {
+            denied_companies,
+            ..self
+        }
+    }
+
+    /// Skip Pythons with a version lower than this one, e.g., the lowest version a project
+    /// supports. Pythons without a version are kept, since their version is only known after
+    /// probing.
+    #[must_use]
+    pub fn with_min_version(self, min_version: Option<PythonVersion>) -> Self {
+        Self {
+            min_version,
+            ..self
+        }
+    }
+
+    /// Only include Pythons of these implementations, see [`WindowsPython::implementation`]. All
+    /// implementations are included if the list is empty, the default.
+    ///
+    /// Pythons of an unknown implementation are kept, since their implementation is only known
+    /// after probing.
+    #[must_use]
+    pub fn with_implementations(self, implementations: Vec<ImplementationName>) -> Self {
+        Self {
+            implementations,
+            ..self
+        }
+    }
+
+    /// Skip Pythons without a readable version, instead of returning them to be probed with
+    /// [`WindowsPython::version_or_infer`], e.g., for listing without running any interpreter.
+    #[must_use]
+    pub fn with_strict(self, strict: bool) -> Self {
+        Self { strict, ..self }
+    }
+
+    /// Include Pythons whose executable doesn't exist, e.g., leftovers of an uninstalled
+    /// distribution, the default.
+    ///
+    /// Discovery skips such entries when probing the interpreter fails, so checking the executable
+    /// during the scan is opt-in.
+    #[must_use]
+    pub fn with_include_broken(self, include_broken: bool) -> Self {
+        Self {
+            include_broken,
+            ..self
+        }
+    }
+
+    /// Bound the duration of the scan, e.g., when registry filter drivers stall individual
+    /// registry operations.
+    ///
+    /// The scan runs on a separate thread, which is abandoned when the timeout is exceeded.
+    #[must_use]
+    pub fn with_timeout(self, timeout: Option<Duration>) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// Include Python 2 installations, which are skipped by default.
+    #[must_use]
+    pub fn with_include_python2(self, include_python2: bool) -> Self {
+        Self {
+            include_python2,
+            ..self
+        }
+    }
+
+    /// Resolve symlinks and junctions in the executables of the entries into
+    /// [`WindowsPython::real_path`], and only return the first entry, in the order of the roots,
+    /// of interpreters registered more than once under different paths.
+    ///
+    /// [`WindowsPython::path`] remains the registered path.
+    #[must_use]
+    pub fn with_resolve_executables(self, resolve_executables: bool) -> Self {
+        Self {
+            resolve_executables,
+            ..self
+        }
+    }
+
+    /// The order of the returned Pythons, by default the highest version first.
+    #[must_use]
+    pub fn with_sort_order(self, sort_order: RegistrySortOrder) -> Self {
+        Self { sort_order, ..self }
+    }
+
+    /// Keys below the roots to scan in addition to `Software\Python`, for distributions that
+    /// register their Pythons in the PEP 514 layout under another key, e.g., `Software\Vendor`.
+    #[must_use]
+    pub fn with_additional_base_paths(self, additional_base_paths: Vec<String>) -> Self {
+        Self {
+            additional_base_paths,
+            ..self
+        }
+    }
+
+    /// Log a single summary of the entries skipped during the scan, e.g., `3 without
+    /// ExecutablePath`, instead of a message for each skipped entry, for scanning machines with
+    /// many invalid entries.
+    #[must_use]
+    pub fn with_summarize_skipped(self, summarize_skipped: bool) -> Self {
+        Self {
+            summarize_skipped,
+            ..self
+        }
+    }
+
+    /// Stop the scan at the next entry once the flag is set, e.g., when an interactive request
+    /// was superseded, and return the Pythons found so far.
+    #[must_use]
+    pub fn with_cancellation(self, cancelled: Arc<AtomicBool>) -> Self {
+        Self {
+            cancelled: Some(cancelled),
+            ..self
+        }
+    }
+
+    /// Only include Pythons built for the architecture of the machine, e.g., skip 32-bit Pythons on
+    /// 64-bit Windows and x86-64 Pythons, which run emulated, on ARM64 Windows.
+    ///
+    /// If the entry doesn't tell the architecture, it is read from the executable. Pythons with an
+    /// unknown architecture are skipped. If the architecture of the machine is unknown, all Pythons
+    /// are included.
+    #[must_use]
+    pub fn with_native_only(self, native_only: bool) -> Self {
+        Self {
+            native_only,
+            ..self
+        }
+    }
+
+    /// Whether the scan was cancelled with [`RegistryScanOptions::with_cancellation`].
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .as_ref()
+            .is_some_and(|cancelled| cancelled.load(atomic::Ordering::Relaxed))
+    }
+
+    /// Whether the entries of the company should be scanned. Company names are case-insensitive.
+    fn includes_company(&self, company: &str) -> bool {
+        if !self.allowed_companies.is_empty()
+            && !self
+                .allowed_companies
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(company))
+        {
+            return false;
+        }
+        !self
+            .denied_companies
+            .iter()
+            .any(|denied| denied.eq_ignore_ascii_case(company))
+    }
+
+    /// Whether a Python found in the registry should be returned.
+    fn includes_python(&self, python: &WindowsPython, skipped: &mut SkippedEntries) -> bool {
+        if !self.include_python2 && python.is_legacy() {
+            skipped.skip("Python 2", &python.path.display());
+            return false;
+        }
+        if self.strict && python.version.is_none() {
+            skipped.skip("without a readable version", &python.path.display());
+            return false;
+        }
+        if !self.implementations.is_empty() {
+            if let Some(implementation) = python.implementation() {
+                if !self.implementations.contains(&implementation) {
+                    skipped.skip("of another implementation", &python.path.display());
+                    return false;
+                }
+            }
+        }
+        if !self.include_broken && !python.path.is_file() {
+            skipped.skip("with a missing executable", &python.path.display());
+            return false;
+        }
+        if self.native_only {
+            if let Some(native_arch) = native_arch() {
+                let arch = python.arch.or_else(|| read_executable_arch(&python.path));
+                if arch.is_none_or(|arch| arch.family() != native_arch.family()) {
+                    skipped.skip(
+                        "not built for the native architecture",
+                        &python.path.display(),
+                    );
+                    return false;
+                }
+            }
+        }
+        match (&self.min_version, &python.version) {
+            (Some(min_version), Some(version)) => version.version() >= min_version.version(),
+            _ => true,
+        }
+    }
+}
+
+/// Find all Pythons registered in the Windows registry following PEP 514.
+pub fn registry_pythons() -> Result<Vec<WindowsPython>, windows_result::Error> {
+    registry_pythons_with(&RegistryScanOptions::default())
+}
+
+/// Find the uv-managed Pythons registered in the Windows registry, i.e., those below our
+/// [`COMPANY_KEY`].
+pub fn managed_registry_pythons() -> Result<Vec<WindowsPython>, windows_result::Error> {
+    registry_pythons_with(
+        &RegistryScanOptions::default().with_allowed_companies(vec![COMPANY_KEY.to_string()]),
+    )
+}
+
+/// Find the uv-managed Python with the highest version registered in the Windows registry.
+///
+/// Entries without a version are ignored.
+pub fn latest_managed_registry_python() -> Option<WindowsPython> {
+    match managed_registry_pythons() {
+        // The entries are sorted by descending version.
+        Ok(pythons) => pythons.into_iter().find(|python| python.version.is_some()),
         Err(err) => {
-            // TODO(konsti): We don't have an installation key here.
-            warn_user_once!("Failed to list subkeys of HKCU:\\{astral_key}: {err}");
+            debug!("Failed to read managed Pythons from the registry: {err}");
+            None
+        }
+    }
+}
+
+/// Find the Pythons registered in the Windows registry that are not managed by uv, i.e., those
+/// of all companies except our [`COMPANY_KEY`].
+pub fn external_registry_pythons() -> Result<Vec<WindowsPython>, windows_result::Error> {
+    registry_pythons_with(
+        &RegistryScanOptions::default().with_denied_companies(vec![COMPANY_KEY.to_string()]),
+    )
+}
+
+/// The result of the last [`cached_registry_pythons`] call, with the PEP 514 key it was read from.
+static REGISTRY_CACHE: Mutex<Option<(String, Vec<WindowsPython>)>> = Mutex::new(None);
+
+/// Like [`registry_pythons`], but reuses the result of previous calls until
+/// [`invalidate_registry_cache`] is called.
+///
+/// This is intended for long-running processes, which should either invalidate the cache when they
+/// change the installed Pythons or use [`watch_registry_changes`]. Changes to our own entries
+/// invalidate the cache automatically.
+pub fn cached_registry_pythons() -> Result<Vec<WindowsPython>, windows_result::Error> {
+    let python_key = python_key();
+    let mut cache = REGISTRY_CACHE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some((cached_key, registry_pythons)) = cache.as_ref() {
+        if *cached_key == python_key {
+            return Ok(registry_pythons.clone());
+        }
+    }
+    let registry_pythons = registry_pythons()?;
+    *cache = Some((python_key, registry_pythons.clone()));
+    Ok(registry_pythons)
+}
+
+/// Clear the cache of [`cached_registry_pythons`], e.g., after a Python was installed or
+/// uninstalled.
+pub fn invalidate_registry_cache() {
+    *REGISTRY_CACHE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = None;
+}
+
+/// Invalidate the cache of [`cached_registry_pythons`] whenever the PEP 514 entries change.
+///
+/// This starts a background thread per PEP 514 key that waits for registry change notifications,
+/// so there's no polling. Like the scan, this includes the 32-bit view of `HKEY_LOCAL_MACHINE`,
+/// see [`RegistryRoot::open_views`]. Calling this more than once has no effect. Roots without a
+/// PEP 514 key aren't watched.
+pub fn watch_registry_changes() {
+    static WATCHING: AtomicBool = AtomicBool::new(false);
+    if WATCHING.swap(true, atomic::Ordering::SeqCst) {
+        return;
+    }
+    for (root, view) in watched_views(&python_key()) {
+        #[cfg(test)]
+        let python_key_override = PYTHON_KEY_OVERRIDE.with_borrow(Clone::clone);
+        let spawned = std::thread::Builder::new()
+            .name(format!("uv-registry-watch-{root}-{view:?}"))
+            .spawn(move || {
+                #[cfg(test)]
+                PYTHON_KEY_OVERRIDE.set(python_key_override);
+                // Registry handles can't be sent between threads, so we open the key here.
+                let key = match root.open_view(view, &python_key()) {
+                    Ok(key) => key,
+                    Err(err) => {
+                        debug!("Not watching {root} ({view:?}) for registry changes: {err}");
+                        return;
+                    }
+                };
+                watch_registry_key(&root, &key);
+            });
+        if let Err(err) = spawned {
+            debug!("Failed to start watching the registry: {err}");
+        }
+    }
+}
+
+/// The roots and views that have the key at `path`, see [`RegistryRoot::open_views`].
+fn watched_views(path: &str) -> Vec<(RegistryRoot, RegistryView)> {
+    RegistryScanOptions::default()
+        .roots
+        .into_iter()
+        .flat_map(|root| {
+            root.open_views(path)
+                .into_iter()
+                .map(move |(view, _)| (root.clone(), view))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Invalidate the registry cache on each change below the key, until watching fails.
+fn watch_registry_key(root: &RegistryRoot, key: &Key) {
+    loop {
+        // SAFETY: The key is valid, and without an event, the call blocks until a change
+        // happens.
+        #[allow(unsafe_code)]
+        let result = unsafe {
+            RegNotifyChangeKeyValue(
+                key.as_raw(),
+                1,
+                REG_NOTIFY_CHANGE_NAME | REG_NOTIFY_CHANGE_LAST_SET,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        // Without a watcher, the cache could go stale, so we invalidate it either way.
+        invalidate_registry_cache();
+        if result != ERROR_SUCCESS {
+            debug!(
+                "Stopped watching {root} for registry changes: {}",
+                windows_result::Error::from_hresult(HRESULT::from_win32(result))
+            );
             return;
         }
+        debug!("Registry entries under {root} changed, invalidated the registry cache");
+    }
+}
+
+/// A hash of the state of the PEP 514 keys, for detecting changes across processes, e.g., to reuse
+/// the result of an earlier scan stored on disk.
+///
+/// Instead of reading and parsing the values, this only hashes the names and the last write times
+/// of the keys, which change whenever a key or a value below them is created, changed or removed.
+/// The hash is stable across processes and uv versions as long as the registry doesn't change.
+pub fn registry_state_hash() -> u64 {
+    registry_state_hash_with(&query_key_info)
+}
+
+/// Like [`registry_state_hash`], but with the metadata of the keys from `key_info`, so that tests
+/// don't depend on the resolution of the last write times.
+fn registry_state_hash_with(key_info: &impl Fn(&Key) -> windows_result::Result<KeyInfo>) -> u64 {
+    let mut hasher = CacheKeyHasher::new();
+    python_key().hash(&mut hasher);
+    for root in RegistryScanOptions::default().roots {
+        root.to_string().hash(&mut hasher);
+        for key_python in root.open_python_views() {
+            hash_key_state(&key_python, &mut hasher, key_info);
+        }
+    }
+    hasher.finish()
+}
+
+/// Hash the names and the last write times of a key and its subkeys recursively, see
+/// [`registry_state_hash`].
+fn hash_key_state(
+    key: &Key,
+    hasher: &mut CacheKeyHasher,
+    key_info: &impl Fn(&Key) -> windows_result::Result<KeyInfo>,
+) {
+    match key_info(key) {
+        Ok(info) => {
+            info.value_count.hash(hasher);
+            info.last_write_time.hash(hasher);
+        }
+        Err(err) => err.code().0.hash(hasher),
+    }
+    let Ok(names) = key.keys() else {
+        return;
+    };
+    for name in names {
+        name.hash(hasher);
+        if let Ok(subkey) = key.open(&name) {
+            hash_key_state(&subkey, hasher, key_info);
+        }
+    }
+}
+
+/// The metadata of a registry key that `windows_registry` doesn't expose.
+struct KeyInfo {
+    /// The number of values of the key.
+    value_count: u32,
+    /// The last time the key or one of its values was written, as a `FILETIME`, i.e., in
+    /// 100-nanosecond intervals since January 1, 1601 (UTC).
+    last_write_time: u64,
+}
+
+/// Query the metadata of a key.
+fn query_key_info(key: &Key) -> windows_result::Result<KeyInfo> {
+    let mut value_count = 0;
+    let mut last_write_time = FILETIME {
+        dwLowDateTime: 0,
+        dwHighDateTime: 0,
     };
-    for subkey in subkeys {
-        if keep.contains(&subkey) {
+    // SAFETY: The key is valid, and all output parameters we don't need are null.
+    #[allow(unsafe_code)]
+    let result = unsafe {
+        RegQueryInfoKeyW(
+            key.as_raw(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &mut value_count,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &mut last_write_time,
+        )
+    };
+    if result != ERROR_SUCCESS {
+        return Err(windows_result::Error::from_hresult(HRESULT::from_win32(
+            result,
+        )));
+    }
+    Ok(KeyInfo {
+        value_count,
+        last_write_time: (u64::from(last_write_time.dwHighDateTime) << 32)
+            | u64::from(last_write_time.dwLowDateTime),
+    })
+}
+
+/// The last time the key or one of its values was written.
+fn key_last_write_time(key: &Key) -> Option<SystemTime> {
+    // The number of 100-nanosecond intervals between the `FILETIME` epoch, 1601, and 1970.
+    const UNIX_EPOCH_AS_FILETIME: u64 = 116_444_736_000_000_000;
+    let last_write_time = query_key_info(key).ok()?.last_write_time;
+    let since_unix_epoch = last_write_time.checked_sub(UNIX_EPOCH_AS_FILETIME)?;
+    SystemTime::UNIX_EPOCH.checked_add(Duration::from_nanos(since_unix_epoch.checked_mul(100)?))
+}
+
+/// Find the Pythons registered in the Windows registry following PEP 514 that match the options.
+pub fn registry_pythons_with(
+    options: &RegistryScanOptions,
+) -> Result<Vec<WindowsPython>, windows_result::Error> {
+    let mut registry_pythons = if let Some(timeout) = options.timeout {
+        collect_registry_pythons_with_timeout(options, timeout)?
+    } else {
+        let mut registry_pythons = Vec::new();
+        walk_registry_pythons(options, |registry_python| {
+            registry_pythons.push(registry_python);
+            ControlFlow::Continue(())
+        })?;
+        registry_pythons
+    };
+
+    if options.resolve_executables {
+        let mut seen = HashSet::new();
+        registry_pythons.retain_mut(|registry_python| {
+            registry_python.real_path = registry_python.path.simple_canonicalize().ok();
+            let Some(real_path) = &registry_python.real_path else {
+                return true;
+            };
+            if seen.insert(real_path.clone()) {
+                true
+            } else {
+                debug!(
+                    "Skipping registry entry `{}`, the same interpreter is already registered",
+                    registry_python.path.display()
+                );
+                false
+            }
+        });
+    }
+
+    match options.sort_order {
+        RegistrySortOrder::Version => registry_pythons.sort_by(cmp_by_version),
+        RegistrySortOrder::Recency => registry_pythons.sort_by(cmp_by_recency),
+    }
+
+    Ok(registry_pythons)
+}
+
+/// Like [`registry_pythons_with`], but scanning on a blocking thread to not block the async
+/// runtime.
+///
+/// Dropping the future cancels the scan, which stops at the next entry. The partial result is
+/// discarded.
+pub async fn registry_pythons_async(
+    options: RegistryScanOptions,
+) -> Result<Vec<WindowsPython>, windows_result::Error> {
+    /// Cancel the scan if the future is dropped before the scan finished.
+    struct CancelOnDrop(Arc<AtomicBool>);
+
+    impl Drop for CancelOnDrop {
+        fn drop(&mut self) {
+            self.0.store(true, atomic::Ordering::Relaxed);
+        }
+    }
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let _cancel_on_drop = CancelOnDrop(cancelled.clone());
+    let options = options.with_cancellation(cancelled);
+    #[cfg(test)]
+    let python_key_override = PYTHON_KEY_OVERRIDE.with_borrow(Clone::clone);
+    let scan = tokio::task::spawn_blocking(move || {
+        #[cfg(test)]
+        PYTHON_KEY_OVERRIDE.set(python_key_override);
+        registry_pythons_with(&options)
+    });
+    match scan.await {
+        Ok(result) => result,
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
+
+/// Call `visitor` for each Python registered in the Windows registry as soon as its entry is read,
+/// e.g., for showing the Pythons in a UI while the scan is still running.
+///
+/// Unlike [`registry_pythons`], the Pythons are in registry order instead of sorted by version.
+/// Invalid entries are skipped.
+pub fn registry_pythons_visit(
+    mut visitor: impl FnMut(WindowsPython),
+) -> Result<(), windows_result::Error> {
+    walk_registry_pythons(&RegistryScanOptions::default(), |python| {
+        visitor(python);
+        ControlFlow::Continue(())
+    })
+}
+
+/// Order the most recently written entries first, by the last write time of their tag key, see
+/// [`RegistrySortOrder::Recency`]. Entries written at the same time are ordered by version.
+fn cmp_by_recency(a: &WindowsPython, b: &WindowsPython) -> Ordering {
+    // Entries without a last write time are sorted last.
+    b.last_write_time
+        .cmp(&a.last_write_time)
+        .then_with(|| cmp_by_version(a, b))
+}
+
+/// The registry has no natural ordering, so we're processing the latest version first.
+fn cmp_by_version(a: &WindowsPython, b: &WindowsPython) -> Ordering {
+    match (&a.version, &b.version) {
+        // Place entries with a version before those without a version.
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        // We want the highest version on top, which is the inverse from the regular order. The
+        // path is an arbitrary but stable tie-breaker.
+        (Some(version_a), Some(version_b)) => version_a
+            .cmp(version_b)
+            .reverse()
+            .then_with(|| cmp_paths(&a.path, &b.path)),
+        // Sort the entries without a version arbitrarily, but stable (by path).
+        (None, None) => cmp_paths(&a.path, &b.path),
+    }
+}
+
+/// Compare paths case-insensitively, like Windows does, so that the order doesn't depend on the
+/// casing of the paths in the registry. Paths that only differ in casing are ordered
+/// case-sensitively to remain deterministic.
+fn cmp_paths(a: &Path, b: &Path) -> Ordering {
+    let a = a.as_os_str().to_string_lossy();
+    let b = b.as_os_str().to_string_lossy();
+    a.to_lowercase()
+        .cmp(&b.to_lowercase())
+        .then_with(|| a.cmp(&b))
+}
+
+/// Collect the Pythons from [`walk_registry_pythons`] on a separate thread, returning the Pythons
+/// found so far if the scan doesn't finish within the timeout.
+fn collect_registry_pythons_with_timeout(
+    options: &RegistryScanOptions,
+    timeout: Duration,
+) -> Result<Vec<WindowsPython>, windows_result::Error> {
+    let options = options.clone();
+    #[cfg(test)]
+    let python_key_override = PYTHON_KEY_OVERRIDE.with_borrow(Clone::clone);
+    collect_with_timeout(timeout, move |visit| {
+        #[cfg(test)]
+        PYTHON_KEY_OVERRIDE.set(python_key_override);
+        walk_registry_pythons(&options, visit)
+    })
+}
+
+/// Run `walk` on a separate thread, collecting the Pythons it visits until it finishes or the
+/// timeout is exceeded. On timeout, the thread is abandoned, and its next visit breaks the walk.
+fn collect_with_timeout(
+    timeout: Duration,
+    walk: impl FnOnce(
+            &mut dyn FnMut(WindowsPython) -> ControlFlow<()>,
+        ) -> Result<(), windows_result::Error>
+        + Send
+        + 'static,
+) -> Result<Vec<WindowsPython>, windows_result::Error> {
+    let deadline = Instant::now() + timeout;
+    let (sender, receiver) = mpsc::channel();
+    let scan = std::thread::spawn(move || {
+        walk(&mut |registry_python| {
+            // If the receiver is gone, the scan timed out and nobody needs the remaining entries.
+            if sender.send(registry_python).is_err() {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+    });
+
+    let mut registry_pythons = Vec::new();
+    loop {
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(registry_python) => registry_pythons.push(registry_python),
+            Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Timeout) => {
+                warn!(
+                    "Reading Pythons from the registry took longer than {}s, \
+                    continuing with the {} Pythons found so far",
+                    timeout.as_secs_f32(),
+                    registry_pythons.len()
+                );
+                return Ok(registry_pythons);
+            }
+        }
+    }
+    // The sender is dropped when the scan finishes, so this doesn't block.
+    match scan.join() {
+        Ok(result) => result.map(|()| registry_pythons),
+        Err(panic) => std::panic::resume_unwind(panic),
+    }
+}
+
+/// Find the Python the `py` launcher would run by default.
+///
+/// Like the launcher, the default is read from [`EnvVars::PY_PYTHON`] or from the `python` setting
+/// in the `[defaults]` section of `%LOCALAPPDATA%\py.ini`, e.g., `3.12` or `3.12-32`. Without a
+/// configured default, or if no registered Python matches it, the highest version is used.
+pub fn py_launcher_default_python() -> Result<Option<WindowsPython>, windows_result::Error> {
+    let registry_pythons = registry_pythons()?;
+    let default = std::env::var(EnvVars::PY_PYTHON).ok().or_else(|| {
+        let local_app_data = std::env::var_os(EnvVars::LOCALAPPDATA)?;
+        let py_ini = fs_err::read_to_string(Path::new(&local_app_data).join("py.ini")).ok()?;
+        parse_py_ini_default(&py_ini)
+    });
+    if let Some(default) = default.as_deref().map(str::trim) {
+        if let Some(python) = registry_pythons
+            .iter()
+            .find(|python| matches_py_launcher_tag(python, default))
+        {
+            return Ok(Some(python.clone()));
+        }
+        debug!("No registered Python matches the default of the `py` launcher `{default}`");
+    }
+    Ok(registry_pythons
+        .into_iter()
+        .find(|python| python.version.is_some()))
+}
+
+/// Read the `python` setting of the `[defaults]` section of a `py.ini`.
+fn parse_py_ini_default(py_ini: &str) -> Option<String> {
+    let mut in_defaults = false;
+    for line in py_ini.lines() {
+        let line = line.trim();
+        if line.starts_with(';') || line.starts_with('#') {
             continue;
         }
-        let python_entry = format!("{astral_key}\\{subkey}");
-        debug!("Removing orphan registry key HKCU:\\{}", python_entry);
-        if let Err(err) = CURRENT_USER.remove_tree(&python_entry) {
-            // TODO(konsti): We don't have an installation key here.
-            warn_user_once!("Failed to remove orphan registry key HKCU:\\{python_entry}: {err}");
-        };
+        if let Some(section) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            in_defaults = section.trim().eq_ignore_ascii_case("defaults");
+            continue;
+        }
+        if !in_defaults {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            if key.trim().eq_ignore_ascii_case("python") && !value.trim().is_empty() {
+                return Some(value.trim().to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Whether a Python matches a version tag of the `py` launcher, such as `3`, `3.12`, `3.12-32` or
+/// `3.12-arm64`.
+fn matches_py_launcher_tag(python: &WindowsPython, tag: &str) -> bool {
+    let (version, platform) = match tag.split_once('-') {
+        Some((version, platform)) => (version, Some(platform)),
+        None => (tag, None),
+    };
+    let Some(registered) = &python.version else {
+        return false;
+    };
+    let mut requested = version.split('.');
+    let matches_version = requested
+        .next()
+        .is_some_and(|major| major.parse() == Ok(registered.major()))
+        && requested
+            .next()
+            .is_none_or(|minor| minor.parse() == Ok(registered.minor()));
+    if !matches_version {
+        return false;
+    }
+    match platform {
+        None => true,
+        Some("32") => python.pointer_width == Some(PointerWidth::U32),
+        Some("64") => python.pointer_width == Some(PointerWidth::U64),
+        Some(platform) if platform.eq_ignore_ascii_case("arm64") => python
+            .arch
+            .is_some_and(|arch| matches!(arch.family(), target_lexicon::Architecture::Aarch64(_))),
+        Some(_) => false,
+    }
+}
+
+/// Whether there is any Python registered in the Windows registry following PEP 514.
+///
+/// Unlike [`registry_pythons`], this stops at the first valid entry.
+pub fn has_registry_pythons() -> bool {
+    let mut found = false;
+    let result = walk_registry_pythons(&RegistryScanOptions::default(), |_| {
+        found = true;
+        ControlFlow::Break(())
+    });
+    if let Err(err) = result {
+        debug!("Failed to read Pythons from the registry: {err}");
+    }
+    found
+}
+
+/// The number of Pythons [`registry_pythons`] would return, without collecting and sorting them.
+pub fn registry_python_count() -> usize {
+    let mut count = 0;
+    let result = walk_registry_pythons(&RegistryScanOptions::default(), |_| {
+        count += 1;
+        ControlFlow::Continue(())
+    });
+    if let Err(err) = result {
+        debug!("Failed to read Pythons from the registry: {err}");
+    }
+    count
+}
+
+/// The distinct major and minor versions of the Pythons [`registry_pythons`] would return, e.g.,
+/// for checking whether any Python 3.11 is registered.
+///
+/// Pythons without a version are excluded.
+pub fn registry_python_minor_versions() -> BTreeSet<(u8, u8)> {
+    let mut minor_versions = BTreeSet::new();
+    let result = walk_registry_pythons(&RegistryScanOptions::default(), |python| {
+        minor_versions.extend(python.major_minor());
+        ControlFlow::Continue(())
+    });
+    if let Err(err) = result {
+        debug!("Failed to read Pythons from the registry: {err}");
+    }
+    minor_versions
+}
+
+/// A company registered below the PEP 514 key, see [`registry_companies`].
+#[derive(Debug, Clone)]
+pub struct CompanyInfo {
+    /// The root the company is registered under.
+    pub root: RegistryRoot,
+    /// The name of the company key, e.g., `PythonCore`.
+    pub name: String,
+    /// The optional `DisplayName` of the company, e.g., `Python Software Foundation`.
+    pub display_name: Option<String>,
+    /// The number of tags of the company, including invalid entries.
+    pub tag_count: usize,
+}
+
+/// List the companies that registered Pythons, without reading the individual interpreters.
+///
+/// A company registered in both registry views of a root, possibly with a different casing, is
+/// listed once, with the tags of both views.
+pub fn registry_companies() -> Vec<CompanyInfo> {
+    let mut companies = Vec::new();
+    for root in RegistryScanOptions::default().roots {
+        for key_python in root.open_python_views() {
+            let names = match key_python.keys() {
+                Ok(names) => names,
+                Err(err) => {
+                    debug!("Failed to list the companies under {root}: {err}");
+                    continue;
+                }
+            };
+            for name in names {
+                if is_reserved_company(&name) {
+                    continue;
+                }
+                let Ok(company_key) = key_python.open(&name) else {
+                    continue;
+                };
+                let display_name = read_string_value(&company_key, "DisplayName")
+                    .ok()
+                    .filter(|display_name| !display_name.trim().is_empty());
+                let tag_count = company_key.keys().map_or(0, Iterator::count);
+                merge_company(
+                    &mut companies,
+                    CompanyInfo {
+                        root: root.clone(),
+                        name,
+                        display_name,
+                        tag_count,
+                    },
+                );
+            }
+        }
+    }
+    companies
+}
+
+/// Add a company to the list, or merge it into the same company of the same root, comparing the
+/// names case-insensitively like the registry.
+///
+/// The first non-empty `DisplayName` is kept.
+fn merge_company(companies: &mut Vec<CompanyInfo>, company: CompanyInfo) {
+    let Some(existing) = companies.iter_mut().find(|existing| {
+        existing.root == company.root && existing.name.eq_ignore_ascii_case(&company.name)
+    }) else {
+        companies.push(company);
+        return;
+    };
+    existing.tag_count += company.tag_count;
+    if existing.display_name.is_none() {
+        existing.display_name = company.display_name;
+    }
+}
+
+/// The Pythons registered by one company, see [`registry_pythons_by_company`].
+#[derive(Debug, Clone)]
+pub struct CompanyGroup {
+    /// The root the company is registered under.
+    pub root: RegistryRoot,
+    /// The name of the company key, e.g., `PythonCore`.
+    pub company: String,
+    /// The optional `DisplayName` of the company, e.g., `Python Software Foundation`.
+    pub display_name: Option<String>,
+    /// The Pythons of the company, one per tag.
+    pub pythons: Vec<WindowsPython>,
+}
+
+/// Find the Pythons registered in the Windows registry grouped by root and company, mirroring the
+/// structure of the registry, e.g., for a tree view.
+///
+/// Companies without valid entries are omitted.
+pub fn registry_pythons_by_company() -> Result<Vec<CompanyGroup>, windows_result::Error> {
+    Ok(group_by_company(&registry_companies(), registry_pythons()?))
+}
+
+/// Group Pythons by root and company, in the order of the first Python of each company.
+fn group_by_company(companies: &[CompanyInfo], pythons: Vec<WindowsPython>) -> Vec<CompanyGroup> {
+    let mut groups: Vec<CompanyGroup> = Vec::new();
+    for python in pythons {
+        let (Some(root), Some(company)) = (python.root.clone(), python.company.clone()) else {
+            continue;
+        };
+        if let Some(group) = groups
+            .iter_mut()
+            .find(|group| group.root == root && group.company.eq_ignore_ascii_case(&company))
+        {
+            group.pythons.push(python);
+            continue;
+        }
+        let display_name = companies
+            .iter()
+            .filter(|info| info.root == root && info.name.eq_ignore_ascii_case(&company))
+            .find_map(|info| info.display_name.clone());
+        groups.push(CompanyGroup {
+            root,
+            company,
+            display_name,
+            pythons: vec![python],
+        });
+    }
+    groups
+}
+
+/// The `DisplayName`s used by Pythons of more than one company, e.g., two vendors both
+/// registering `Python 3.12 (64-bit)`.
+///
+/// Listings can't tell these Pythons apart by name, so they should show the company too, see
+/// [`WindowsPython::unambiguous_display_name`]. Multiple Pythons of the same company with the same
+/// name aren't reported, since the company doesn't disambiguate them.
+pub fn duplicate_display_names(pythons: &[WindowsPython]) -> HashSet<String> {
+    let mut companies: HashMap<&str, &str> = HashMap::new();
+    let mut duplicates = HashSet::new();
+    for python in pythons {
+        let (Some(display_name), Some(company)) = (&python.display_name, &python.company) else {
+            continue;
+        };
+        match companies.get(display_name.as_str()) {
+            Some(first) if !first.eq_ignore_ascii_case(company) => {
+                if duplicates.insert(display_name.clone()) {
+                    debug!(
+                        "The registry `DisplayName` `{display_name}` is used by both `{first}` \
+                        and `{company}`"
+                    );
+                }
+            }
+            Some(_) => {}
+            None => {
+                companies.insert(display_name, company);
+            }
+        }
+    }
+    duplicates
+}
+
+/// Explain why `python` doesn't run a registered interpreter, if `python.exe` on `PATH` resolves to
+/// the Microsoft Store alias, which opens the Store instead of running Python.
+///
+/// `None` if `python.exe` on `PATH` isn't the alias, or if no Python is registered.
+pub fn store_shim_shadowing_note() -> Option<String> {
+    let path_python = which::which("python.exe").ok()?;
+    if !crate::discovery::is_windows_store_shim(&path_python) {
+        return None;
+    }
+    let registry_pythons = registry_pythons()
+        .inspect_err(|err| debug!("Failed to read Pythons from the registry: {err}"))
+        .ok()?;
+    store_shim_shadowing_message(&path_python, &registry_pythons)
+}
+
+fn store_shim_shadowing_message(shim: &Path, registry_pythons: &[WindowsPython]) -> Option<String> {
+    // The Pythons are sorted by version, so we suggest the highest version.
+    let python = registry_pythons
+        .iter()
+        .find(|python| !is_same_path(&python.path, shim))?;
+    let registered = match &python.version {
+        Some(version) => format!("Python {version}"),
+        None => "a Python".to_string(),
+    };
+    Some(format!(
+        "`python` on `PATH` is the Microsoft Store alias `{}`, but {registered} is registered at \
+        `{}`. Add `{}` to `PATH` before the alias, or disable the alias in Settings under \
+        \"App execution aliases\"",
+        shim.user_display(),
+        python.path.user_display(),
+        python.path.parent().unwrap_or(&python.path).user_display(),
+    ))
+}
+
+/// The Pythons of one version, e.g., the x86 and x86-64 builds of Python 3.12.8, see
+/// [`group_by_version`].
+#[derive(Debug, Clone)]
+pub struct VersionGroup {
+    /// The version of the Pythons, `None` for the group of Pythons without a version.
+    pub version: Option<PythonVersion>,
+    /// The architecture and the executable of each Python of the version.
+    pub pythons: Vec<(Option<Arch>, PathBuf)>,
+}
+
+/// Group Pythons from the registry by version, for listing one row per version with the
+/// available architectures.
+///
+/// The groups are in the order of the first Python of each version, so sorted Pythons result in
+/// sorted groups. Pythons without a version are grouped together.
+pub fn group_by_version(pythons: &[WindowsPython]) -> Vec<VersionGroup> {
+    let mut groups: Vec<VersionGroup> = Vec::new();
+    for python in pythons {
+        let entry = (python.arch, python.path.clone());
+        if let Some(group) = groups
+            .iter_mut()
+            .find(|group| group.version == python.version)
+        {
+            group.pythons.push(entry);
+        } else {
+            groups.push(VersionGroup {
+                version: python.version.clone(),
+                pythons: vec![entry],
+            });
+        }
+    }
+    groups
+}
+
+/// Dump the raw PEP 514 subtree of both roots and both registry views as JSON, for attaching to
+/// bug reports.
+///
+/// Unlike [`registry_pythons`], this doesn't interpret the entries: It includes all keys and
+/// values with their types, including invalid entries and values unknown to uv. Keys that can't be
+/// opened are recorded with their error instead.
+pub fn dump_registry_pythons_json() -> String {
+    let mut roots = serde_json::Map::new();
+    for (name, root) in [("HKCU", CURRENT_USER), ("HKLM", LOCAL_MACHINE)] {
+        let mut views = serde_json::Map::new();
+        for (view, access) in [("64-bit", KEY_WOW64_64KEY), ("32-bit", KEY_WOW64_32KEY)] {
+            let dump = match open_key_with_access(root, &python_key(), KEY_READ | access) {
+                Ok(key) => dump_registry_key(&key, KEY_READ | access),
+                Err(err) if err.code() == ERROR_NOT_FOUND => serde_json::Value::Null,
+                Err(err) => serde_json::json!({ "error": err.to_string() }),
+            };
+            views.insert(view.to_string(), dump);
+        }
+        roots.insert(name.to_string(), serde_json::Value::Object(views));
+    }
+    let dump = serde_json::json!({
+        "key": python_key(),
+        "roots": roots,
+    });
+    serde_json::to_string_pretty(&dump).expect("JSON values are always serializable")
+}
+
+/// Dump the values and the subkeys of a key recursively, see [`dump_registry_pythons_json`].
+///
+/// Subkeys are opened with the same access rights, so that they stay in the view of the parent.
+fn dump_registry_key(key: &Key, access: REG_SAM_FLAGS) -> serde_json::Value {
+    let mut dump = serde_json::Map::new();
+    let mut errors = Vec::new();
+    let mut values = serde_json::Map::new();
+    match key.values() {
+        Ok(iter) => {
+            for (name, value) in iter {
+                values.insert(name, dump_registry_value(&value));
+            }
+        }
+        Err(err) => errors.push(format!("Failed to read values: {err}")),
+    }
+    dump.insert("values".to_string(), serde_json::Value::Object(values));
+    let mut keys = serde_json::Map::new();
+    match key.keys() {
+        Ok(iter) => {
+            for name in iter {
+                let subkey = match open_key_with_access(key, &name, access) {
+                    Ok(subkey) => dump_registry_key(&subkey, access),
+                    Err(err) => serde_json::json!({ "error": err.to_string() }),
+                };
+                keys.insert(name, subkey);
+            }
+        }
+        Err(err) => errors.push(format!("Failed to read subkeys: {err}")),
+    }
+    dump.insert("keys".to_string(), serde_json::Value::Object(keys));
+    if !errors.is_empty() {
+        dump.insert("error".to_string(), errors.join(", ").into());
+    }
+    serde_json::Value::Object(dump)
+}
+
+/// Dump a registry value with its type, see [`dump_registry_pythons_json`].
+fn dump_registry_value(value: &Value) -> serde_json::Value {
+    let wide_string = || {
+        let wide = value.as_wide();
+        let wide = wide.strip_suffix(&[0]).unwrap_or(wide);
+        String::from_utf16_lossy(wide)
+    };
+    let bytes = || {
+        value.iter().fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+    };
+    let (ty, data) = match value.ty() {
+        Type::U32 => (
+            "REG_DWORD".to_string(),
+            u32::try_from(value.clone()).map_or_else(|_| bytes().into(), serde_json::Value::from),
+        ),
+        Type::U64 => (
+            "REG_QWORD".to_string(),
+            u64::try_from(value.clone()).map_or_else(|_| bytes().into(), serde_json::Value::from),
+        ),
+        Type::String => ("REG_SZ".to_string(), wide_string().into()),
+        Type::ExpandString => ("REG_EXPAND_SZ".to_string(), wide_string().into()),
+        Type::MultiString => (
+            "REG_MULTI_SZ".to_string(),
+            wide_string()
+                .split('\0')
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+        Type::Bytes => ("REG_BINARY".to_string(), bytes().into()),
+        Type::Other(ty) => (format!("{ty}"), bytes().into()),
+    };
+    serde_json::json!({
+        "type": ty,
+        "data": data,
+    })
+}
+
+/// A snapshot of our registry entries, see [`export_managed_registry`].
+///
+/// The snapshot uses the format of [`dump_registry_pythons_json`] for our company key, with all
+/// values of all tags, so it can be restored on another machine or user profile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SerializedRegistry(serde_json::Value);
+
+impl SerializedRegistry {
+    /// Serialize the snapshot as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.0).expect("JSON values are always serializable")
+    }
+
+    /// Read a snapshot serialized with [`SerializedRegistry::to_json`].
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json).map(Self)
+    }
+}
+
+/// An error restoring a [`SerializedRegistry`].
+#[derive(Debug, Error)]
+pub enum RegistryImportError {
+    #[error("Invalid registry snapshot: {0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Registry(#[from] windows_result::Error),
+}
+
+/// Snapshot our registry entries in `HKEY_CURRENT_USER`, for restoring them with
+/// [`import_managed_registry`], e.g., before a risky operation.
+pub fn export_managed_registry() -> Result<SerializedRegistry, windows_result::Error> {
+    match CURRENT_USER.open(format!("{}\\{COMPANY_KEY}", python_key())) {
+        Ok(key) => Ok(SerializedRegistry(dump_registry_key(&key, KEY_READ))),
+        Err(err) if err.code() == ERROR_NOT_FOUND => Ok(SerializedRegistry(
+            serde_json::json!({ "values": {}, "keys": {} }),
+        )),
+        Err(err) => Err(err),
+    }
+}
+
+/// Restore a snapshot of [`export_managed_registry`] below our company key in
+/// `HKEY_CURRENT_USER`.
+///
+/// The keys and values of the snapshot are written over the existing entries, other entries are
+/// kept. Nothing is written outside of our company key.
+pub fn import_managed_registry(snapshot: &SerializedRegistry) -> Result<(), RegistryImportError> {
+    let company = RegistryRoot::CurrentUser.create_below_python_key(COMPANY_KEY)?;
+    let result = import_registry_key(&company, &snapshot.0);
+    invalidate_registry_cache();
+    result
+}
+
+/// Write the values and the subkeys of a dump of [`dump_registry_key`] below the key.
+fn import_registry_key(key: &Key, dump: &serde_json::Value) -> Result<(), RegistryImportError> {
+    let Some(dump) = dump.as_object() else {
+        return Err(RegistryImportError::Invalid(
+            "expected an object for each key".to_string(),
+        ));
+    };
+    for (name, value) in dump
+        .get("values")
+        .and_then(|values| values.as_object())
+        .into_iter()
+        .flatten()
+    {
+        let data = &value["data"];
+        let ty = value["type"].as_str().unwrap_or_default();
+        let invalid_data =
+            || RegistryImportError::Invalid(format!("invalid data for `{name}` of type `{ty}`"));
+        match ty {
+            "REG_SZ" => key.set_string(name.as_str(), data.as_str().ok_or_else(invalid_data)?)?,
+            "REG_EXPAND_SZ" => {
+                key.set_expand_string(name.as_str(), data.as_str().ok_or_else(invalid_data)?)?;
+            }
+            "REG_MULTI_SZ" => {
+                let lines = data
+                    .as_array()
+                    .ok_or_else(invalid_data)?
+                    .iter()
+                    .map(|line| line.as_str().ok_or_else(invalid_data))
+                    .collect::<Result<Vec<_>, _>>()?;
+                key.set_multi_string(name.as_str(), &lines)?;
+            }
+            "REG_DWORD" => key.set_u32(
+                name,
+                data.as_u64()
+                    .and_then(|data| u32::try_from(data).ok())
+                    .ok_or_else(invalid_data)?,
+            )?,
+            "REG_QWORD" => key.set_u64(name, data.as_u64().ok_or_else(invalid_data)?)?,
+            _ => {
+                // Binary values and values of other types are dumped as hex bytes.
+                let ty = match ty {
+                    "REG_BINARY" => Type::Bytes,
+                    other => Type::Other(other.parse().map_err(|_| {
+                        RegistryImportError::Invalid(format!("unknown type `{other}` of `{name}`"))
+                    })?),
+                };
+                let bytes = data
+                    .as_str()
+                    .and_then(parse_hex_bytes)
+                    .ok_or_else(invalid_data)?;
+                key.set_bytes(name, ty, &bytes)?;
+            }
+        }
+    }
+    for (name, subkey) in dump
+        .get("keys")
+        .and_then(|keys| keys.as_object())
+        .into_iter()
+        .flatten()
+    {
+        validate_registry_key_name(name).map_err(|reason| {
+            RegistryImportError::Invalid(format!("invalid key name `{name}`: {reason}"))
+        })?;
+        import_registry_key(&key.create(name)?, subkey)?;
+    }
+    Ok(())
+}
+
+/// Parse the hex bytes of [`dump_registry_value`], e.g., `00ff`.
+fn parse_hex_bytes(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|start| u8::from_str_radix(hex.get(start..start + 2)?, 16).ok())
+        .collect()
+}
+
+/// Whether a Python matching the request is registered in the Windows registry or installed from
+/// the Microsoft Store, stopping at the first match.
+pub fn windows_python_available(request: &VersionRequest) -> bool {
+    let allow_prereleases = request.allows_prereleases();
+    let mut found = false;
+    let result = walk_registry_pythons(&RegistryScanOptions::default(), |python| {
+        if python.matches_version_request(request, allow_prereleases) {
+            found = true;
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    if let Err(err) = result {
+        debug!("Failed to read Pythons from the registry: {err}");
+    }
+    found
+        || find_microsoft_store_pythons()
+            .any(|python| python.matches_version_request(request, allow_prereleases))
+}
+
+/// Find the Python with the highest version of the implementation matching the version request in
+/// the registry, without collecting all Pythons, e.g., for `uv python find cpython@3.12`.
+///
+/// The implementation is guessed from the company, see [`WindowsPython::implementation`], so
+/// Pythons of unknown distributions never match. Of Pythons with the same version, the first in
+/// the order of the roots is returned.
+pub fn find_registry_python_for(
+    implementation: ImplementationName,
+    request: &VersionRequest,
+) -> Option<WindowsPython> {
+    let allow_prereleases = request.allows_prereleases();
+    let mut best: Option<WindowsPython> = None;
+    let result = walk_registry_pythons(&RegistryScanOptions::default(), |python| {
+        if python.implementation() == Some(implementation)
+            && python.matches_version_request(request, allow_prereleases)
+            && best.as_ref().is_none_or(|best| {
+                python.version.as_ref().map(PythonVersion::version)
+                    > best.version.as_ref().map(PythonVersion::version)
+            })
+        {
+            best = Some(python);
+        }
+        ControlFlow::Continue(())
+    });
+    if let Err(err) = result {
+        debug!("Failed to read Pythons from the registry: {err}");
+    }
+    best
+}
+
+/// Call `visit` for each Python registered in the registry that matches the options, in registry
+/// order, until it returns [`ControlFlow::Break`].
+#[instrument(
+    name = "registry_scan",
+    level = "debug",
+    skip_all,
+    fields(roots = options.roots.len())
+)]
+fn walk_registry_pythons(
+    options: &RegistryScanOptions,
+    visit: impl FnMut(WindowsPython) -> ControlFlow<()>,
+) -> Result<(), windows_result::Error> {
+    if registry_discovery_disabled() {
+        debug!(
+            "Skipping Pythons from the registry, registry discovery is disabled with `{}`",
+            EnvVars::UV_PYTHON_NO_REGISTRY
+        );
+        return Ok(());
+    }
+    let mut skipped = SkippedEntries {
+        summarize: options.summarize_skipped,
+        ..SkippedEntries::default()
+    };
+    let result = walk_registry_roots(options, &mut skipped, visit);
+    skipped.log_summary();
+    result
+}
+
+fn walk_registry_roots(
+    options: &RegistryScanOptions,
+    skipped: &mut SkippedEntries,
+    mut visit: impl FnMut(WindowsPython) -> ControlFlow<()>,
+) -> Result<(), windows_result::Error> {
+    let default_preference = machine_default_preference();
+    let base_paths: Vec<String> = std::iter::once(python_key())
+        .chain(options.additional_base_paths.iter().cloned())
+        .collect();
+    for root in &options.roots {
+        for (base_path, (view, key_python)) in base_paths.iter().flat_map(|path| {
+            root.open_views(path)
+                .into_iter()
+                .map(move |view| (path, view))
+        }) {
+            for company in key_python.keys()? {
+                if is_reserved_company(&company) {
+                    continue;
+                }
+                if !options.includes_company(&company) {
+                    debug!("Skipping registry entries of company `{company}`");
+                    continue;
+                }
+                if known_distribution(&company).is_some_and(|known| !known.is_supported()) {
+                    debug!("Skipping registry entries of unsupported company `{company}`");
+                    continue;
+                }
+                let _span = debug_span!("registry_company", %root, %company).entered();
+                let company_key = match key_python.open(&company) {
+                    Ok(company_key) => company_key,
+                    Err(err) => {
+                        if err.code() == ERROR_DENIED {
+                            skipped.access_denied(root, format!(r"{root}\{base_path}\{company}"));
+                        }
+                        // Ignore invalid entries
+                        continue;
+                    }
+                };
+                let layout = registry_layout(&company, &company_key);
+                let company_display_name = read_string_value(&company_key, "DisplayName").ok();
+                for tag in company_key.keys()? {
+                    // Our alias duplicates the entry of another installation.
+                    if company.eq_ignore_ascii_case(COMPANY_KEY)
+                        && tag.eq_ignore_ascii_case(LATEST_TAG)
+                    {
+                        continue;
+                    }
+                    if options.is_cancelled() {
+                        debug!("Registry scan was cancelled");
+                        return Ok(());
+                    }
+                    let tag_key = match company_key.open(&tag) {
+                        Ok(tag_key) => tag_key,
+                        Err(err) if err.code() == ERROR_DENIED => {
+                            skipped.access_denied(
+                                root,
+                                format!(r"{root}\{base_path}\{company}\{tag}"),
+                            );
+                            continue;
+                        }
+                        Err(err) => return Err(err),
+                    };
+
+                    if let Some(mut registry_python) =
+                        read_registry_entry(&company, &tag, &tag_key, layout, view, skipped)
+                    {
+                        registry_python.root = Some(root.clone());
+                        if registry_python.install_source.is_none() {
+                            registry_python
+                                .install_source
+                                .clone_from(&company_display_name);
+                        }
+                        registry_python.default_designation = default_preference
+                            .as_ref()
+                            .filter(|default_preference| {
+                                default_preference.matches(&company, &tag, &registry_python)
+                            })
+                            .map(DefaultPreference::designation);
+                        if options.includes_python(&registry_python, skipped) {
+                            if visit(registry_python).is_break() {
+                                return Ok(());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The registry entries skipped during a scan, see [`RegistryScanOptions::with_summarize_skipped`].
+#[derive(Debug, Default)]
+struct SkippedEntries {
+    /// Count the skipped entries by reason instead of logging each one.
+    summarize: bool,
+    counts: BTreeMap<&'static str, usize>,
+    /// The keys below `HKEY_LOCAL_MACHINE` the user isn't allowed to read.
+    access_denied: Vec<String>,
+}
+
+impl SkippedEntries {
+    /// Record a skipped entry, such as the tag key or the executable.
+    fn skip(&mut self, reason: &'static str, entry: &dyn std::fmt::Display) {
+        if self.summarize {
+            *self.counts.entry(reason).or_default() += 1;
+        } else {
+            debug!("Skipping registry entry `{entry}`: {reason}");
+        }
+    }
+
+    /// Record a company or tag key that couldn't be read due to missing permissions.
+    ///
+    /// Limited users may not be allowed to read all machine-wide entries, which would otherwise
+    /// silently differ from the Pythons found in an elevated shell.
+    fn access_denied(&mut self, root: &RegistryRoot, key: String) {
+        debug!("Skipping registry key `{key}`: access denied");
+        if *root == RegistryRoot::LocalMachine {
+            self.access_denied.push(key);
+        }
+    }
+
+    /// Log the counts of the skipped entries, if they were summarized, and warn about the keys that
+    /// couldn't be read.
+    fn log_summary(&self) {
+        if !self.counts.is_empty() {
+            debug!(
+                "Skipped registry entries: {}",
+                self.counts
+                    .iter()
+                    .map(|(reason, count)| format!("{count} {reason}"))
+                    .join(", ")
+            );
+        }
+        if !self.access_denied.is_empty() {
+            warn_user_once!(
+                "Some machine-wide Pythons may be hidden due to permissions, access to the \
+                registry {} {} was denied",
+                if self.access_denied.len() == 1 {
+                    "key"
+                } else {
+                    "keys"
+                },
+                self.access_denied
+                    .iter()
+                    .map(|key| format!("`{key}`"))
+                    .join(", ")
+            );
+        }
+    }
+}
+
+/// The name of the value below our [`COMPANY_KEY`] in `HKEY_LOCAL_MACHINE` with which
+/// administrators can designate the default Python of the machine by its registry entry, as
+/// `<company>\<tag>`, e.g., `PythonCore\3.12`.
+const DEFAULT_TAG_VALUE: &str = "DefaultTag";
+
+/// Like [`DEFAULT_TAG_VALUE`], but designating the default Python by its version, in the format of
+/// the `py` launcher, e.g., `3.12` or `3.12-32`. [`DEFAULT_TAG_VALUE`] takes precedence.
+const DEFAULT_VERSION_VALUE: &str = "DefaultVersion";
+
+/// The default Python designated by an administrator, see [`DEFAULT_TAG_VALUE`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DefaultPreference {
+    Tag { company: String, tag: String },
+    Version(String),
+}
+
+/// How an administrator designated a Python as the default of the machine, see
+/// [`WindowsPython::default_designation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefaultDesignation {
+    /// By company and tag, with [`DEFAULT_TAG_VALUE`].
+    Tag,
+    /// By a version in the format of the `py` launcher, with [`DEFAULT_VERSION_VALUE`].
+    Version,
+}
+
+impl DefaultPreference {
+    fn designation(&self) -> DefaultDesignation {
+        match self {
+            Self::Tag { .. } => DefaultDesignation::Tag,
+            Self::Version(_) => DefaultDesignation::Version,
+        }
+    }
+
+    /// Whether the entry of the company and tag is the designated default.
+    fn matches(&self, company: &str, tag: &str, python: &WindowsPython) -> bool {
+        match self {
+            Self::Tag {
+                company: default_company,
+                tag: default_tag,
+            } => {
+                default_company.eq_ignore_ascii_case(company)
+                    && default_tag.eq_ignore_ascii_case(tag)
+            }
+            Self::Version(version) => matches_py_launcher_tag(python, version),
+        }
+    }
+}
+
+/// Read the default Python designated by an administrator below our company key in
+/// `HKEY_LOCAL_MACHINE`, which users can't change.
+fn machine_default_preference() -> Option<DefaultPreference> {
+    let company_key = open_native_view(LOCAL_MACHINE, &python_key())
+        .ok()?
+        .open(COMPANY_KEY)
+        .ok()?;
+    read_default_preference(&company_key)
+}
+
+/// Read a [`DefaultPreference`] from the values of a company key.
+fn read_default_preference(company_key: &Key) -> Option<DefaultPreference> {
+    if let Ok(value) = read_string_value(company_key, DEFAULT_TAG_VALUE) {
+        if let Some((company, tag)) = value.trim().split_once('\\') {
+            debug!("Using the default Python `{value}` designated in the registry");
+            return Some(DefaultPreference::Tag {
+                company: company.to_string(),
+                tag: tag.to_string(),
+            });
+        }
+        debug!("Ignoring invalid `{DEFAULT_TAG_VALUE}` `{value}`, expected `<company>\\<tag>`");
+    }
+    let value = read_string_value(company_key, DEFAULT_VERSION_VALUE).ok()?;
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    debug!("Using the default Python version `{value}` designated in the registry");
+    Some(DefaultPreference::Version(value.to_string()))
+}
+
+/// The company names reserved by PEP 514, which don't contain Python registrations.
+const RESERVED_COMPANIES: &[&str] = &[
+    // The settings of the `py` launcher.
+    "PyLauncher",
+];
+
+/// Whether the company name is reserved by PEP 514 for other purposes, see
+/// [`RESERVED_COMPANIES`]. Like all registry key names, company names are case-insensitive.
+pub fn is_reserved_company(name: &str) -> bool {
+    RESERVED_COMPANIES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(name))
+}
+
+/// Whether registry discovery is disabled with [`EnvVars::UV_PYTHON_NO_REGISTRY`], which is read
+/// once per process.
+fn registry_discovery_disabled() -> bool {
+    static DISABLED: LazyLock<bool> = LazyLock::new(|| {
+        std::env::var(EnvVars::UV_PYTHON_NO_REGISTRY)
+            .is_ok_and(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+    });
+    *DISABLED
+}
+
+/// Open a key, such as the PEP 514 key, below `root` in the native registry view, i.e., the 64-bit
+/// view on 64-bit Windows.
+///
+/// Without explicit access rights, the registry view depends on the bitness of the process: For a
+/// 32-bit process on 64-bit Windows, `HKEY_LOCAL_MACHINE\Software` is redirected to
+/// `WOW6432Node`, which would hide the machine-wide 64-bit Pythons.
+fn open_native_view(root: &Key, path: &str) -> windows_result::Result<Key> {
+    open_key_with_access(root, path, KEY_READ | KEY_WOW64_64KEY)
+}
+
+/// Open a key below `root` in the 32-bit registry view (`WOW6432Node`), independent of
+/// the bitness of the process.
+///
+/// On 32-bit Windows, this is the same as [`open_native_view`].
+fn open_wow64_view(root: &Key, path: &str) -> windows_result::Result<Key> {
+    open_key_with_access(root, path, KEY_READ | KEY_WOW64_32KEY)
+}
+
+/// The access flag selecting the registry view of an interpreter with the given pointer width in
+/// bits.
+fn registry_view_access(pointer_width: i32) -> REG_SAM_FLAGS {
+    if pointer_width == 64 {
+        KEY_WOW64_64KEY
+    } else {
+        KEY_WOW64_32KEY
+    }
+}
+
+/// Whether the current process is a 32-bit process running on 64-bit Windows.
+fn is_wow64_process() -> bool {
+    let mut is_wow64 = 0;
+    // SAFETY: The pseudo handle of the current process is always valid.
+    #[allow(unsafe_code)]
+    let success = unsafe { IsWow64Process(GetCurrentProcess(), &mut is_wow64) != 0 };
+    success && is_wow64 != 0
+}
+
+/// Open a registry key with the given access rights, which `windows_registry` doesn't support.
+fn open_key_with_access(
+    parent: &Key,
+    path: &str,
+    access: REG_SAM_FLAGS,
+) -> windows_result::Result<Key> {
+    let path = HSTRING::from(path);
+    let mut handle = std::ptr::null_mut();
+    // SAFETY: The path is null-terminated and the parent is a valid key.
+    #[allow(unsafe_code)]
+    let result = unsafe { RegOpenKeyExW(parent.as_raw(), path.as_ptr(), 0, access, &mut handle) };
+    if result != ERROR_SUCCESS {
+        return Err(windows_result::Error::from_hresult(HRESULT::from_win32(
+            result,
+        )));
+    }
+    // SAFETY: We own the handle opened above, which `Key` closes on drop.
+    #[allow(unsafe_code)]
+    Ok(unsafe { Key::from_raw(handle) })
+}
+
+/// Create or open a registry key with the given access rights, which `windows_registry` doesn't
+/// support.
+fn create_key_with_access(
+    parent: &Key,
+    path: &str,
+    access: REG_SAM_FLAGS,
+) -> windows_result::Result<Key> {
+    let path = HSTRING::from(path);
+    let mut handle = std::ptr::null_mut();
+    // SAFETY: The path is null-terminated, the parent is a valid key, and the optional
+    // parameters are null.
+    #[allow(unsafe_code)]
+    let result = unsafe {
+        RegCreateKeyExW(
+            parent.as_raw(),
+            path.as_ptr(),
+            0,
+            std::ptr::null(),
+            REG_OPTION_NON_VOLATILE,
+            access,
+            std::ptr::null(),
+            &mut handle,
+            std::ptr::null_mut(),
+        )
+    };
+    if result != ERROR_SUCCESS {
+        return Err(windows_result::Error::from_hresult(HRESULT::from_win32(
+            result,
+        )));
+    }
+    // SAFETY: We own the handle created above, which `Key` closes on drop.
+    #[allow(unsafe_code)]
+    Ok(unsafe { Key::from_raw(handle) })
+}
+
+/// Determine the layout of the entries of a company.
+///
+/// Only our own company carries a schema version, all other companies are plain PEP 514.
+fn registry_layout(company: &str, company_key: &Key) -> RegistryLayout {
+    if !company.eq_ignore_ascii_case(COMPANY_KEY) {
+        return RegistryLayout::Pep514;
+    }
+    let schema = company_key.get_u32(REGISTRY_SCHEMA_VALUE).unwrap_or(0);
+    if schema > REGISTRY_SCHEMA_VERSION {
+        debug!(
+            "Registry entries under `{}\\{company}` use schema version {schema}, \
+            newer than the supported version {REGISTRY_SCHEMA_VERSION}, reading known values only",
+            python_key()
+        );
+    }
+    RegistryLayout::Managed(schema)
+}
+
+fn read_registry_entry(
+    company: &str,
+    tag: &str,
+    tag_key: &Key,
+    layout: RegistryLayout,
+    view: RegistryView,
+    skipped: &mut SkippedEntries,
+) -> Option<WindowsPython> {
+    // `ExecutablePath` is mandatory for executable Pythons.
+    let install_path = tag_key.open("InstallPath");
+    let executable_path = match &install_path {
+        Ok(install_path) => read_string_value(install_path, "ExecutablePath").or_else(|err| {
+            if err.code() != ERROR_NOT_FOUND {
+                return Err(err);
+            }
+            ALTERNATE_EXECUTABLE_VALUES
+                .iter()
+                .find_map(|name| {
+                    let executable_path = read_string_value(install_path, name).ok()?;
+                    debug!(
+                        "Registry entry `{}\\{company}\\{tag}` has no `ExecutablePath`, using \
+                            the `{name}` value of `InstallPath`",
+                        python_key(),
+                    );
+                    Some(executable_path)
+                })
+                .ok_or(err)
+        }),
+        // Some non-conforming registrations put `ExecutablePath` directly on the tag.
+        Err(err) if err.code() == ERROR_NOT_FOUND => read_string_value(tag_key, "ExecutablePath")
+            .inspect(|_| {
+                debug!(
+                    "Registry entry `{}\\{company}\\{tag}` has no `InstallPath`, using the \
+                    `ExecutablePath` of the tag",
+                    python_key(),
+                );
+            }),
+        Err(err) => Err(err.clone()),
+    };
+    let Ok(executable_path) = executable_path else {
+        let entry = format!(r"{}\{company}\{tag}", python_key());
+        skipped.skip("without `ExecutablePath`", &entry);
+        return None;
+    };
+    if executable_path.trim().is_empty() {
+        let entry = format!(r"{}\{company}\{tag}", python_key());
+        skipped.skip("with an empty `ExecutablePath`", &entry);
+        return None;
+    }
+
+    // The installation directory is the default (unnamed) value of `InstallPath`.
+    let prefix = install_path
+        .as_ref()
+        .ok()
+        .and_then(|install_path| read_string_value(install_path, "").ok())
+        .filter(|prefix| !prefix.trim().is_empty())
+        .map(PathBuf::from);
+
+    // Some registrations store the executables relative to the installation directory.
+    let executable_path = match resolve_relative_to_prefix(&executable_path, prefix.as_deref()) {
+        Some(resolved) => {
+            let resolved = resolved.to_string_lossy().into_owned();
+            debug!(
+                "Resolved the relative `ExecutablePath` `{executable_path}` of registry entry \
+                `{}\\{company}\\{tag}` to `{resolved}`",
+                python_key(),
+            );
+            resolved
+        }
+        None => executable_path,
+    };
+
+    // `SysVersion` and `Version` are optional. We prefer `SysVersion`, since some distributions
+    // use `Version` for their own release number.
+    let sys_version = read_version_value(tag_key, "SysVersion", &executable_path);
+    let full_version = read_version_value(tag_key, "Version", &executable_path);
+    if let (Some(sys_version), Some(full_version)) = (&sys_version, &full_version) {
+        // A different major version means the entry is broken, but there's nothing the user can
+        // do about third-party registrations, so we only log it.
+        if sys_version.major() != full_version.major() {
+            warn!(
+                "The registry entry `{}\\{company}\\{tag}` has inconsistent versions \
+                (`SysVersion`: {sys_version}, `Version`: {full_version}), using `SysVersion`",
+                python_key(),
+            );
+        }
+    }
+    // `SysVersion` is usually only the minor version and can't express pre-releases, so we use
+    // `Version` if it is a patch version or pre-release of the same minor version, e.g., `3.12.4`
+    // or `3.13.0rc1` for `3.13`. uv writes the pre-release only to `Version`.
+    let version = match (sys_version, full_version.clone()) {
+        (Some(sys_version), Some(full_version))
+            if (full_version.pre().is_some()
+                || full_version.release().len() > sys_version.release().len())
+                && (full_version.major(), full_version.minor())
+                    == (sys_version.major(), sys_version.minor()) =>
+        {
+            Some(full_version)
+        }
+        (sys_version, _) => sys_version,
+    }
+    .or_else(|| match layout {
+        // Our `Version` is always a Python version.
+        RegistryLayout::Managed(_) => full_version.clone(),
+        // Some older registrations only have a major version tag, such as `PythonCore\3`.
+        RegistryLayout::Pep514 => tag
+            .bytes()
+            .all(|byte| byte.is_ascii_digit())
+            .then(|| PythonVersion::from_str(tag).ok())
+            .flatten()
+            .filter(|version| u8::try_from(version.release()[0]).is_ok()),
+    });
+
+    // Runtime requirements are an optional, non-standard annotation.
+    let runtime_requirements = tag_key
+        .get_multi_string(RUNTIME_REQUIREMENTS_VALUE)
+        .map(|requirements| {
+            requirements
+                .into_iter()
+                .map(|requirement| requirement.trim().to_string())
+                .filter(|requirement| !requirement.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // The variant and base version are only written by uv.
+    let (variant, base_version) = match layout {
+        RegistryLayout::Managed(schema) if schema >= 2 => (
+            read_variant_value(tag_key, &executable_path),
+            read_version_value(tag_key, BASE_VERSION_VALUE, &executable_path),
+        ),
+        RegistryLayout::Managed(_) | RegistryLayout::Pep514 => (None, None),
+    };
+
+    // The archive hash is only written by uv, and only for installations from a download.
+    let download_sha256 = match layout {
+        RegistryLayout::Managed(_) => read_string_value(tag_key, "DownloadSha256")
+            .ok()
+            .filter(|sha256| !sha256.is_empty()),
+        RegistryLayout::Pep514 => None,
+    };
+
+    let display_name = read_string_value(tag_key, "DisplayName")
+        .ok()
+        .map(|display_name| display_name.trim().to_string())
+        .filter(|display_name| !display_name.is_empty());
+
+    // The company `DisplayName` is the fallback, which is set by the walk.
+    let install_source = read_string_value(tag_key, INSTALL_SOURCE_VALUE)
+        .ok()
+        .map(|install_source| install_source.trim().to_string())
+        .filter(|install_source| !install_source.is_empty());
+
+    let executable_arguments = install_path
+        .as_ref()
+        .map(read_executable_arguments)
+        .unwrap_or_default();
+
+    // Not all distributions have a separate GUI executable, some repeat the console executable.
+    let windowed_path = install_path
+        .ok()
+        .and_then(|install_path| read_string_value(&install_path, "WindowedExecutablePath").ok())
+        .filter(|windowed_path| !windowed_path.is_empty())
+        .map(|windowed_path| {
+            resolve_relative_to_prefix(&windowed_path, prefix.as_deref())
+                .unwrap_or_else(|| PathBuf::from(windowed_path))
+        })
+        .filter(|windowed_path| !is_same_path(windowed_path, Path::new(&executable_path)));
+
+    let pointer_width = read_pointer_width(tag, tag_key, view);
+    let arch = read_arch_hint(
+        company,
+        tag,
+        tag_key,
+        layout,
+        pointer_width,
+        &executable_path,
+    );
+
+    // Aliases are an opt-in convention for our own entries.
+    let aliases = match layout {
+        RegistryLayout::Managed(_) => tag_key
+            .get_multi_string(ALIASES_VALUE)
+            .map(|aliases| {
+                aliases
+                    .into_iter()
+                    .map(|alias| alias.trim().to_string())
+                    .filter(|alias| !alias.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        RegistryLayout::Pep514 => Vec::new(),
+    };
+
+    let build_variant = read_build_variant(tag, tag_key, variant, display_name.as_deref());
+
+    Some(WindowsPython {
+        path: PathBuf::from(executable_path),
+        prefix,
+        version,
+        full_version,
+        runtime_requirements,
+        variant,
+        base_version,
+        download_sha256,
+        arch,
+        pointer_width,
+        windowed_path,
+        executable_arguments,
+        aliases,
+        last_write_time: key_last_write_time(tag_key),
+        root: None,
+        company: Some(company.to_string()),
+        tag: Some(tag.to_string()),
+        display_name,
+        layout: Some(layout),
+        install_source,
+        default_designation: None,
+        build_variant,
+        real_path: None,
+        inferred_version: OnceLock::new(),
+    })
+}
+
+/// The variant of the build of the entry, preferring explicit values over heuristics.
+///
+/// Only the free-threaded build is detected, for everything else the default variant is assumed.
+///
+/// In order, the hints are our [`VARIANT_VALUE`], the [`FREE_THREADED_VALUE`], a `t` suffix of
+/// the version in the tag as used by the python.org installers, e.g., `3.13t` or `3.13t-32`, and
+/// a `DisplayName` mentioning `freethreaded`, e.g., `Python 3.13 (64-bit, freethreaded)`.
+fn read_build_variant(
+    tag: &str,
+    tag_key: &Key,
+    variant: Option<PythonVariant>,
+    display_name: Option<&str>,
+) -> PythonVariant {
+    if let Some(variant) = variant {
+        return variant;
+    }
+    if let Ok(value) = tag_key.get_value(FREE_THREADED_VALUE) {
+        let explicit = match value.ty() {
+            Type::U32 => u32::try_from(value).ok().map(|value| value != 0),
+            Type::String => String::try_from(value).ok().map(|value| {
+                let value = value.trim();
+                value == "1" || value.eq_ignore_ascii_case("true")
+            }),
+            _ => None,
+        };
+        if let Some(explicit) = explicit {
+            return if explicit {
+                PythonVariant::Freethreaded
+            } else {
+                PythonVariant::Default
+            };
+        }
+    }
+    // Ex) `3.13t-arm64`
+    let version_tag = match tag.rsplit_once('-') {
+        Some((version_tag, _)) if parse_tag_pointer_width(tag).is_some() => version_tag,
+        _ => tag,
+    };
+    if version_tag
+        .strip_suffix(['t', 'T'])
+        .is_some_and(|version| version.ends_with(|c: char| c.is_ascii_digit()))
+    {
+        return PythonVariant::Freethreaded;
+    }
+    if display_name.is_some_and(|display_name| {
+        let display_name = display_name.to_ascii_lowercase();
+        display_name.contains("freethreaded") || display_name.contains("free-threaded")
+    }) {
+        PythonVariant::Freethreaded
+    } else {
+        PythonVariant::Default
+    }
+}
+
+/// Whether the version is a Python 2 version, see [`WindowsPython::is_legacy`].
+fn is_legacy_version(version: Option<&PythonVersion>) -> bool {
+    version.is_some_and(|version| version.major() == 2)
+}
+
+/// Resolve a path from `InstallPath` against the installation directory, if it is relative, e.g.,
+/// `pythonw.exe`.
+///
+/// Returns `None` for absolute paths, which are used as-is, and if the entry has no installation
+/// directory.
+fn resolve_relative_to_prefix(path: &str, prefix: Option<&Path>) -> Option<PathBuf> {
+    let path = Path::new(path.trim());
+    // Paths with a drive or a root, such as `C:python.exe` or `\python.exe`, aren't relative to the
+    // installation directory.
+    let is_relative = matches!(
+        path.components().next(),
+        Some(Component::Normal(_) | Component::CurDir | Component::ParentDir)
+    );
+    is_relative.then(|| prefix.map(|prefix| prefix.join(path)))?
+}
+
+/// Whether two paths point to the same file, comparing them like Windows does.
+///
+/// The paths are compared case-insensitively, treating `/` like `\`. If they differ, but both
+/// exist, they are canonicalized, which resolves symlinks and short names like `PROGRA~1`.
+fn is_same_path(a: &Path, b: &Path) -> bool {
+    let normalize = |path: &Path| {
+        path.to_string_lossy()
+            .replace('/', "\\")
+            .trim_end_matches('\\')
+            .to_lowercase()
+    };
+    if normalize(a) == normalize(b) {
+        return true;
+    }
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => normalize(&a) == normalize(&b),
+        _ => false,
+    }
+}
+
+/// Whether two Pythons, e.g., from different sources, are the same interpreter, because their
+/// executables are the same file, see [`is_same_path`].
+pub fn same_interpreter(a: &WindowsPython, b: &WindowsPython) -> bool {
+    is_same_path(&a.path, &b.path)
+}
+
+/// The Pythons found in the registry compared to the Pythons found on `PATH`, see
+/// [`diff_registry_vs_path`].
+#[derive(Debug, Clone, Default)]
+pub struct RegistryPathDiff {
+    /// Registered Pythons that aren't on `PATH`.
+    pub registry_only: Vec<WindowsPython>,
+    /// Executables on `PATH` that aren't registered.
+    pub path_only: Vec<PathBuf>,
+    /// Registered Pythons that are on `PATH`, with the executable found on `PATH`.
+    pub both: Vec<(WindowsPython, PathBuf)>,
+}
+
+/// Compare the Pythons in the registry to the given executables found on `PATH`, to explain why an
+/// interpreter is found by one but not by the other.
+///
+/// Executables are matched if they are the same file, see [`is_same_path`].
+pub fn diff_registry_vs_path(path_pythons: &[PathBuf]) -> RegistryPathDiff {
+    let registry_pythons = registry_pythons().unwrap_or_else(|err| {
+        debug!("Failed to read Pythons from the registry: {err}");
+        Vec::new()
+    });
+    diff_pythons(registry_pythons, path_pythons)
+}
+
+fn diff_pythons(
+    registry_pythons: Vec<WindowsPython>,
+    path_pythons: &[PathBuf],
+) -> RegistryPathDiff {
+    let mut diff = RegistryPathDiff::default();
+    let mut matched = vec![false; path_pythons.len()];
+    for registry_python in registry_pythons {
+        let position = path_pythons
+            .iter()
+            .position(|path_python| is_same_path(&registry_python.path, path_python));
+        if let Some(position) = position {
+            matched[position] = true;
+            diff.both
+                .push((registry_python, path_pythons[position].clone()));
+        } else {
+            diff.registry_only.push(registry_python);
+        }
+    }
+    diff.path_only = path_pythons
+        .iter()
+        .zip(matched)
+        .filter(|(_, matched)| !matched)
+        .map(|(path_python, _)| path_python.clone())
+        .collect();
+    diff
+}
+
+/// A distribution of Python recognized by the company key of its registry entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnownDistribution {
+    /// The python.org installers.
+    PythonOrg,
+    /// Anaconda and Miniconda.
+    Conda,
+    /// PyPy.
+    PyPy,
+    /// GraalPy.
+    GraalPy,
+    /// Jython, which runs on the JVM and isn't supported by uv.
+    Jython,
+    /// Pythons installed by uv.
+    Uv,
+}
+
+impl KnownDistribution {
+    /// The implementation of the Pythons of the distribution, if it only ships one.
+    pub fn implementation(self) -> Option<ImplementationName> {
+        match self {
+            Self::PythonOrg | Self::Conda => Some(ImplementationName::CPython),
+            Self::PyPy => Some(ImplementationName::PyPy),
+            Self::GraalPy => Some(ImplementationName::GraalPy),
+            Self::Jython | Self::Uv => None,
+        }
+    }
+
+    /// Whether uv can use the Pythons of the distribution.
+    pub fn is_supported(self) -> bool {
+        !matches!(self, Self::Jython)
+    }
+}
+
+/// The company keys of the known distributions, see [`known_distribution`].
+///
+/// Vendors have renamed and versioned their company keys over time, so the names are prefixes,
+/// e.g., `ContinuumAnalytics` also matches the `ContinuumAnalytics64` of old Anaconda releases.
+const KNOWN_DISTRIBUTIONS: &[(&str, KnownDistribution)] = &[
+    ("PythonCore", KnownDistribution::PythonOrg),
+    ("ContinuumAnalytics", KnownDistribution::Conda),
+    ("Anaconda", KnownDistribution::Conda),
+    ("Miniconda", KnownDistribution::Conda),
+    ("PyPy", KnownDistribution::PyPy),
+    ("GraalPy", KnownDistribution::GraalPy),
+    ("Jython", KnownDistribution::Jython),
+    (COMPANY_KEY, KnownDistribution::Uv),
+];
+
+/// The distribution that registers Pythons under the company key, matching the prefixes in
+/// [`KNOWN_DISTRIBUTIONS`] case-insensitively. `None` for other companies.
+pub fn known_distribution(company: &str) -> Option<KnownDistribution> {
+    KNOWN_DISTRIBUTIONS
+        .iter()
+        .find(|(prefix, _)| {
+            company
+                .get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+        })
+        .map(|(_, distribution)| *distribution)
+}
+
+/// Determine the architecture of an interpreter from the hints in its registry entry.
+///
+/// PEP 514 only records the pointer width in `SysArchitecture`, which doesn't distinguish x64
+/// from ARM64, so we use the conventions of the installers where we know them:
+///
+/// * uv installs into directories named after the installation key, such as
+///   `cpython-3.12.8-windows-aarch64-none`.
+/// * The python.org installers register ARM64 builds with an `-arm64` tag suffix, such as
+///   `3.12-arm64`, and 32-bit builds with a `-32` suffix.
+/// * Some installers use `ARM64` as `SysArchitecture`.
+fn read_arch_hint(
+    company: &str,
+    tag: &str,
+    tag_key: &Key,
+    layout: RegistryLayout,
+    pointer_width: Option<PointerWidth>,
+    executable_path: &str,
+) -> Option<Arch> {
+    if let RegistryLayout::Managed(_) = layout {
+        return PathBuf::from(executable_path)
+            .parent()?
+            .file_name()?
+            .to_str()
+            .and_then(|name| PythonInstallationKey::from_str(name).ok())
+            .map(|key| *key.arch());
+    }
+
+    let sys_architecture = read_string_value(tag_key, "SysArchitecture").ok();
+    let tag = tag.to_ascii_lowercase();
+    let family = if tag.ends_with("-arm64")
+        || sys_architecture
+            .as_deref()
+            .is_some_and(|arch| arch.eq_ignore_ascii_case("ARM64"))
+    {
+        target_lexicon::Architecture::Aarch64(target_lexicon::Aarch64Architecture::Aarch64)
+    } else if pointer_width == Some(PointerWidth::U32) {
+        target_lexicon::Architecture::X86_32(target_lexicon::X86_32Architecture::I686)
+    } else if known_distribution(company) == Some(KnownDistribution::PythonOrg)
+        && pointer_width == Some(PointerWidth::U64)
+    {
+        // The python.org installers would have used the `-arm64` suffix for an ARM64 build.
+        target_lexicon::Architecture::X86_64
+    } else {
+        return None;
+    };
+    Some(Arch {
+        family,
+        variant: None,
+    })
+}
+
+/// Read the pointer width of an interpreter from `SysArchitecture`.
+///
+/// Without `SysArchitecture`, entries in a separate registry view are assumed to match its
+/// bitness, e.g., 32-bit for entries in `WOW6432Node`, see [`RegistryView::pointer_width`].
+/// Older python.org installers don't set `SysArchitecture`, but encode the architecture in the tag,
+/// see [`parse_tag_pointer_width`]. Some distributions only annotate the `DisplayName` with the
+/// bitness, e.g., `Example Python 3.12 (64-bit)`, so we use a trailing annotation as a last
+/// fallback.
+fn read_pointer_width(tag: &str, tag_key: &Key, view: RegistryView) -> Option<PointerWidth> {
+    let read_string = |name| read_string_value(tag_key, name).ok();
+    if let Some(sys_architecture) = read_string("SysArchitecture") {
+        match sys_architecture.as_str() {
+            "32bit" => return Some(PointerWidth::U32),
+            "64bit" => return Some(PointerWidth::U64),
+            _ => {}
+        }
+    }
+    view.pointer_width()
+        .or_else(|| parse_tag_pointer_width(tag))
+        .or_else(|| parse_display_name_pointer_width(&read_string("DisplayName")?))
+}
+
+/// Parse the architecture suffix of a tag, as used by the python.org installers, e.g., `3.12-32`.
+///
+/// The recognized suffixes are `-32` for 32-bit, and `-64` and `-arm64` for 64-bit interpreters,
+/// compared case-insensitively.
+fn parse_tag_pointer_width(tag: &str) -> Option<PointerWidth> {
+    let (_, suffix) = tag.rsplit_once('-')?;
+    if suffix == "32" {
+        Some(PointerWidth::U32)
+    } else if suffix == "64" || suffix.eq_ignore_ascii_case("arm64") {
+        Some(PointerWidth::U64)
+    } else {
+        None
+    }
+}
+
+/// Parse a trailing bitness annotation, such as `(64-bit)` or `(32-bit, freethreaded)`, from a
+/// `DisplayName`.
+fn parse_display_name_pointer_width(display_name: &str) -> Option<PointerWidth> {
+    let (_, annotation) = display_name
+        .trim_end()
+        .strip_suffix(')')?
+        .rsplit_once('(')?;
+    match annotation.split(',').next()?.trim() {
+        "32-bit" => Some(PointerWidth::U32),
+        "64-bit" => Some(PointerWidth::U64),
+        _ => None,
+    }
+}
+
+/// Describe how an interpreter with the pointer width and architecture differs from the `native`
+/// architecture of the machine, see [`WindowsPython::host_arch_note`].
+///
+/// A different pointer width is reported first, since it's the more likely cause of failures.
+/// Unknown parts of the interpreter's architecture are assumed to match.
+fn arch_mismatch_note(
+    pointer_width: Option<PointerWidth>,
+    arch: Option<Arch>,
+    native: Arch,
+) -> Option<String> {
+    let native_pointer_width = native.family().pointer_width().ok()?;
+    if let Some(pointer_width) = pointer_width {
+        if pointer_width != native_pointer_width {
+            return Some(format!(
+                "{}-bit, host is {}-bit",
+                pointer_width.bits(),
+                native_pointer_width.bits()
+            ));
+        }
+    }
+    // The same pointer width, but a different architecture, e.g., x64 on Windows on ARM.
+    let arch = arch?;
+    if arch.family() != native.family() {
+        return Some(format!("{arch}, host is {native}"));
+    }
+    None
+}
+
+/// The architecture of the machine, as opposed to the architecture of the current process, which
+/// may be emulated.
+fn native_arch() -> Option<Arch> {
+    let mut process_machine = 0;
+    let mut native_machine = 0;
+    // SAFETY: The pseudo handle of the current process is always valid.
+    #[allow(unsafe_code)]
+    let success = unsafe {
+        IsWow64Process2(
+            GetCurrentProcess(),
+            &mut process_machine,
+            &mut native_machine,
+        ) != 0
+    };
+    if !success {
+        return None;
+    }
+    machine_arch(native_machine)
+}
+
+/// Read the architecture an executable was built for from the machine type of its PE header.
+fn read_executable_arch(executable: &Path) -> Option<Arch> {
+    let mut header = [0; 4096];
+    let len = fs_err::File::open(executable)
+        .and_then(|mut file| io::Read::read(&mut file, &mut header))
+        .ok()?;
+    arch_from_pe_header(&header[..len])
+}
+
+/// The architecture in the PE header at the start of an executable, see
+/// <https://learn.microsoft.com/en-us/windows/win32/debug/pe-format>.
+fn arch_from_pe_header(header: &[u8]) -> Option<Arch> {
+    if !header.starts_with(b"MZ") {
+        return None;
+    }
+    // The offset of the PE signature is at `0x3c` of the DOS header.
+    let offset =
+        usize::try_from(u32::from_le_bytes(header.get(0x3c..0x40)?.try_into().ok()?)).ok()?;
+    if header.get(offset..offset.checked_add(4)?)? != b"PE\0\0" {
+        return None;
+    }
+    let machine = header.get(offset + 4..offset + 6)?;
+    machine_arch(u16::from_le_bytes([machine[0], machine[1]]))
+}
+
+/// The architecture of an `IMAGE_FILE_MACHINE_*` machine type.
+fn machine_arch(machine: u16) -> Option<Arch> {
+    let family = match machine {
+        IMAGE_FILE_MACHINE_ARM64 => {
+            target_lexicon::Architecture::Aarch64(target_lexicon::Aarch64Architecture::Aarch64)
+        }
+        IMAGE_FILE_MACHINE_AMD64 => target_lexicon::Architecture::X86_64,
+        IMAGE_FILE_MACHINE_I386 => {
+            target_lexicon::Architecture::X86_32(target_lexicon::X86_32Architecture::I686)
+        }
+        _ => return None,
+    };
+    Some(Arch {
+        family,
+        variant: None,
+    })
+}
+
+/// Read a string value.
+///
+/// Registry strings may contain unpaired surrogates, which can't be represented in Rust strings.
+/// Instead of discarding the value, e.g., a path that is otherwise valid, we replace them.
+fn read_string_value(key: &Key, name: &str) -> windows_result::Result<String> {
+    let value = key.get_value(name)?;
+    match String::try_from(value.clone()) {
+        Ok(value) => Ok(value),
+        Err(err) if matches!(value.ty(), Type::String | Type::ExpandString) => {
+            let wide = value.as_wide();
+            let wide = wide.strip_suffix(&[0]).unwrap_or(wide);
+            let lossy = String::from_utf16_lossy(wide);
+            debug!("Registry value `{name}` is not valid UTF-16 ({err}), using `{lossy}`");
+            Ok(lossy)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Read the [`EXECUTABLE_ARGUMENTS_VALUE`] of `InstallPath`.
+fn read_executable_arguments(install_path: &Key) -> Vec<String> {
+    let Ok(value) = install_path.get_value(EXECUTABLE_ARGUMENTS_VALUE) else {
+        return Vec::new();
+    };
+    let arguments = match value.ty() {
+        Type::MultiString => Vec::<String>::try_from(value).unwrap_or_default(),
+        Type::String | Type::ExpandString => {
+            read_string_value(install_path, EXECUTABLE_ARGUMENTS_VALUE)
+                .map(|arguments| {
+                    arguments
+                        .split_whitespace()
+                        .map(ToString::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+        _ => Vec::new(),
+    };
+    arguments
+        .into_iter()
+        .map(|argument| argument.trim().to_string())
+        .filter(|argument| !argument.is_empty())
+        .collect()
+}
+
+/// Read and parse a version value of a tag, such as `SysVersion`.
+fn read_version_value(tag_key: &Key, name: &str, executable_path: &str) -> Option<PythonVersion> {
+    let mut value = read_string_value(tag_key, name).ok()?;
+    if let Some(normalized) = normalize_comma_version(&value) {
+        debug!(
+            "Registry version `{name}` {value} of Python interpreter ({executable_path}) uses a \
+            comma as separator, using {normalized}"
+        );
+        value = normalized;
+    }
+    match PythonVersion::from_str(&value) {
+        // `PythonVersion` requires the release segments to fit into a `u8`, which isn't the case
+        // for distributions using calendar versions, e.g., `Version: 2024.10`.
+        Ok(version)
+            if version
+                .release()
+                .iter()
+                .take(3)
+                .any(|segment| u8::try_from(*segment).is_err()) =>
+        {
+            debug!(
+                "Ignoring registry version `{name}` {value} \
+                of Python interpreter ({executable_path}), it is not a Python version"
+            );
+            None
+        }
+        Ok(version) => Some(version),
+        Err(err) => {
+            debug!(
+                "Ignoring invalid registry version `{name}` {value} \
+                of Python interpreter ({executable_path}): {err}",
+            );
+            None
+        }
+    }
+}
+
+/// Replace the commas in a version written with a locale-dependent decimal separator by some
+/// installers, e.g., `3,12` instead of `3.12`.
+///
+/// Returns `None` unless the value consists only of numeric segments separated by commas.
+fn normalize_comma_version(value: &str) -> Option<String> {
+    let value = value.trim();
+    let is_comma_version = value.contains(',')
+        && value.split(',').all(|segment| {
+            !segment.is_empty() && segment.bytes().all(|byte| byte.is_ascii_digit())
+        });
+    is_comma_version.then(|| value.replace(',', "."))
+}
+
+/// Read the [`VARIANT_VALUE`] of one of our tags.
+fn read_variant_value(tag_key: &Key, executable_path: &str) -> Option<PythonVariant> {
+    let value = read_string_value(tag_key, VARIANT_VALUE).ok()?;
+    // We write the `Display` representation, which uses `default` for the default variant.
+    if value == "default" {
+        return Some(PythonVariant::Default);
+    }
+    if let Ok(variant) = PythonVariant::from_str(&value) {
+        Some(variant)
+    } else {
+        debug!(
+            "Ignoring unknown registry variant `{value}` of Python interpreter ({executable_path})"
+        );
+        None
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ManagedPep514Error {
+    #[error("Windows has an unknown pointer width for arch: `{_0}`")]
+    InvalidPointerSize(Arch),
+    #[error("Invalid registry tag `{_0}`: {_1}")]
+    InvalidTag(String, &'static str),
+    #[error(
+        "The executable `{}` is not inside the installation directory `{}`",
+        executable.user_display(),
+        installation.user_display()
+    )]
+    ExecutableOutsideInstallation {
+        executable: PathBuf,
+        installation: PathBuf,
+    },
+    #[error(
+        "Refusing to register `{_0}` in `HKEY_LOCAL_MACHINE` without opting into machine-wide registration"
+    )]
+    LocalMachineNotAllowed(PythonInstallationKey),
+    #[error(
+        "`{_0}` has no registry entry to update, use `create_registry_entry` to register it instead"
+    )]
+    MissingRegistryEntry(PythonInstallationKey),
+    #[error(transparent)]
+    Registry(#[from] windows_result::Error),
+}
+
+/// Register a managed Python installation in the Windows registry following PEP 514.
+pub fn create_registry_entry(
+    installation: &ManagedPythonInstallation,
+    errors: &mut Vec<(PythonInstallationKey, anyhow::Error)>,
+) -> Result<(), ManagedPep514Error> {
+    create_registry_entry_in(
+        &RegistryRoot::CurrentUser,
+        installation,
+        &RegistryMetadata::default(),
+        errors,
+    )
+}
+
+/// The descriptive values of our registry entries, which redistributors of uv may want to point
+/// to their own support channels.
+#[derive(Debug, Clone)]
+pub struct RegistryMetadata {
+    company_display_name: String,
+    support_url: String,
+    aliases: Vec<String>,
+    preserve_windowed_executable_path: bool,
+    description: bool,
+    allow_local_machine: bool,
+    display_name_resource: Option<(PathBuf, u32)>,
+}
+
+impl Default for RegistryMetadata {
+    fn default() -> Self {
+        Self {
+            company_display_name: COMPANY_DISPLAY_NAME.to_string(),
+            support_url: SUPPORT_URL.to_string(),
+            aliases: Vec::new(),
+            preserve_windowed_executable_path: false,
+            description: false,
+            allow_local_machine: false,
+            display_name_resource: None,
+        }
+    }
+}
+
+impl RegistryMetadata {
+    /// The `DisplayName` of the company.
+    #[must_use]
+    pub fn with_company_display_name(self, company_display_name: String) -> Self {
+        Self {
+            company_display_name,
+            ..self
+        }
+    }
+
+    /// The `SupportUrl` of the company and of each tag.
+    #[must_use]
+    pub fn with_support_url(self, support_url: String) -> Self {
+        Self {
+            support_url,
+            ..self
+        }
+    }
+
+    /// Additional names the installation can be requested by, such as `latest`.
+    #[must_use]
+    pub fn with_aliases(self, aliases: Vec<String>) -> Self {
+        Self { aliases, ..self }
+    }
+
+    /// Keep a `WindowedExecutablePath` of an existing entry that was customized by the user or
+    /// another tool, instead of overwriting it with the GUI executable of the installation.
+    #[must_use]
+    pub fn with_preserve_windowed_executable_path(
+        self,
+        preserve_windowed_executable_path: bool,
+    ) -> Self {
+        Self {
+            preserve_windowed_executable_path,
+            ..self
+        }
+    }
+
+    /// Write a [`DESCRIPTION_VALUE`] with the provenance of the installation, see
+    /// [`registry_description`].
+    #[must_use]
+    pub fn with_description(self, description: bool) -> Self {
+        Self {
+            description,
+            ..self
+        }
+    }
+
+    /// Allow registering in `HKEY_LOCAL_MACHINE`, which affects all users of the machine.
+    ///
+    /// Without this, e.g., for an explicit `--system` flag of an elevated process, writing to
+    /// `HKEY_LOCAL_MACHINE` is an error. Reading from it is always allowed.
+    #[must_use]
+    pub fn with_allow_local_machine(self, allow_local_machine: bool) -> Self {
+        Self {
+            allow_local_machine,
+            ..self
+        }
+    }
+
+    /// Write the `DisplayName` of the tag as an indirect string referencing the string resource
+    /// with the given ID in a resource file, e.g., `@C:\uv\uv-resources.dll,-101`, which Windows
+    /// resolves in the language of the user.
+    ///
+    /// The resource is specific to the installation, so it must be set for each registration. If
+    /// the resource file doesn't exist, e.g., in a build without localization resources, the plain
+    /// `DisplayName` from [`registry_display_name`] is written instead.
+    #[must_use]
+    pub fn with_display_name_resource(self, resource_file: PathBuf, resource_id: u32) -> Self {
+        Self {
+            display_name_resource: Some((resource_file, resource_id)),
+            ..self
+        }
+    }
+
+    /// The indirect string for the `DisplayName`, if a resource that exists was requested with
+    /// [`RegistryMetadata::with_display_name_resource`].
+    fn indirect_display_name(&self) -> Option<String> {
+        let (resource_file, resource_id) = self.display_name_resource.as_ref()?;
+        if !resource_file.is_file() {
+            debug!(
+                "Display name resource file `{}` doesn't exist, using the plain display name",
+                resource_file.user_display()
+            );
+            return None;
+        }
+        Some(format!("@{},-{resource_id}", resource_file.display()))
+    }
+}
+
+/// Register a managed Python installation below the given root following PEP 514.
+///
+/// Unlike [`create_registry_entry`], which registers for the current user with the default
+/// metadata, this can register the installation in another user's hive loaded below
+/// `HKEY_USERS`, with custom descriptive values.
+pub fn create_registry_entry_in(
+    root: &RegistryRoot,
+    installation: &ManagedPythonInstallation,
+    metadata: &RegistryMetadata,
+    errors: &mut Vec<(PythonInstallationKey, anyhow::Error)>,
+) -> Result<(), ManagedPep514Error> {
+    let tag = registry_python_tag(installation.key());
+    create_registry_entry_as(root, installation, metadata, &tag, errors)
+}
+
+/// Register a managed Python installation below the given root with the given tag.
+fn create_registry_entry_as(
+    root: &RegistryRoot,
+    installation: &ManagedPythonInstallation,
+    metadata: &RegistryMetadata,
+    tag: &str,
+    errors: &mut Vec<(PythonInstallationKey, anyhow::Error)>,
+) -> Result<(), ManagedPep514Error> {
+    // Machine-wide state must only be modified on explicit request.
+    if *root == RegistryRoot::LocalMachine && !metadata.allow_local_machine {
+        return Err(ManagedPep514Error::LocalMachineNotAllowed(
+            installation.key().clone(),
+        ));
+    }
+    let pointer_width = match installation.key().arch().family().pointer_width() {
+        Ok(PointerWidth::U32) => 32,
+        Ok(PointerWidth::U64) => 64,
+        _ => {
+            return Err(ManagedPep514Error::InvalidPointerSize(
+                *installation.key().arch(),
+            ));
+        }
+    };
+
+    if let Err(reason) = validate_registry_key_name(tag) {
+        errors.push((
+            installation.key().clone(),
+            ManagedPep514Error::InvalidTag(tag.to_string(), reason).into(),
+        ));
+        return Ok(());
+    }
+    // Don't register a misleading entry if we computed the wrong executable path. This only
+    // affects this installation, so the others are still registered.
+    for windowed in [false, true] {
+        let executable = installation.executable(windowed);
+        if !executable.starts_with(installation.path()) {
+            errors.push((
+                installation.key().clone(),
+                ManagedPep514Error::ExecutableOutsideInstallation {
+                    executable,
+                    installation: installation.path().to_path_buf(),
+                }
+                .into(),
+            ));
+            return Ok(());
+        }
+    }
+
+    // Concurrent uv processes, e.g., parallel CI jobs on the same runner, may write to our
+    // company key at the same time, so we retry errors caused by concurrent access.
+    let write = || write_registry_entry(root, installation, metadata, tag, pointer_width);
+    let result = write
+        .retry(backoff_registry_write())
+        .sleep(std::thread::sleep)
+        .when(|err: &RegistryWriteError| is_transient_registry_error(err.registry_error()))
+        .notify(|err, _dur| {
+            warn!(
+                "Retrying registry entry creation for {} due to transient error: {err}",
+                installation.key()
+            );
+        })
+        .call();
+    invalidate_registry_cache();
+    if let Err(err) = result {
+        errors.push((installation.key().clone(), err.into()));
+    }
+
+    Ok(())
+}
+
+/// Rewrite the registry entries of all given managed installations in the current format.
+///
+/// After an upgrade of uv that changed the layout of our entries, this migrates the existing
+/// entries to the current [`REGISTRY_SCHEMA_VERSION`]. Values that are already up to date are
+/// not written again.
+pub fn refresh_all_registry_entries(
+    installations: &[ManagedPythonInstallation],
+    errors: &mut Vec<(PythonInstallationKey, anyhow::Error)>,
+) -> Result<(), ManagedPep514Error> {
+    for installation in installations {
+        create_registry_entry(installation, errors)?;
+    }
+    Ok(())
+}
+
+/// Register the highest version of the given managed installations a second time under the
+/// reserved [`LATEST_TAG`], for tools that resolve Pythons by tag, similar to the `python-latest`
+/// symlinks on Unix.
+///
+/// Call this again after installing or removing installations to update the alias. Without
+/// installations, the alias is removed. uv itself ignores the alias when scanning the registry.
+pub fn register_latest_alias(
+    installations: &[ManagedPythonInstallation],
+    errors: &mut Vec<(PythonInstallationKey, anyhow::Error)>,
+) -> Result<(), ManagedPep514Error> {
+    // Prefer the default variant over, e.g., the free-threaded build of the same version.
+    let latest = installations.iter().max_by(|a, b| {
+        a.key()
+            .version()
+            .cmp(&b.key().version())
+            .then_with(|| b.key().variant().cmp(a.key().variant()))
+    });
+    let Some(latest) = latest else {
+        let alias = format!("{}\\{COMPANY_KEY}\\{LATEST_TAG}", python_key());
+        invalidate_registry_cache();
+        match CURRENT_USER.remove_tree(&alias) {
+            Ok(()) => debug!("Removed registry key HKCU:\\{alias}"),
+            Err(err) if err.code() == ERROR_NOT_FOUND => {}
+            Err(err) => {
+                warn_user_once!("Failed to remove registry key HKCU:\\{alias}: {err}");
+            }
+        }
+        return Ok(());
+    };
+    debug!("Registering {} as `{LATEST_TAG}`", latest.key());
+    create_registry_entry_as(
+        &RegistryRoot::CurrentUser,
+        latest,
+        &RegistryMetadata::default(),
+        LATEST_TAG,
+        errors,
+    )
+}
+
+fn backoff_registry_write() -> backon::ExponentialBackoff {
+    // Registry writes are fast, so we start at 10 milliseconds and retry 5 times, which amounts
+    // to about 300 milliseconds overall.
+    backon::ExponentialBuilder::default()
+        .with_min_delay(Duration::from_millis(10))
+        .with_max_times(5)
+        .build()
+}
+
+/// An error writing the registry entry of a managed installation.
+#[derive(Debug, Error)]
+enum RegistryWriteError {
+    /// Creating our company key failed, e.g., due to a group policy or a corrupted hive, before
+    /// any entry was written.
+    #[error("Failed to create the registry key `{path}`")]
+    CreateCompanyKey {
+        path: String,
+        #[source]
+        err: windows_result::Error,
+    },
+    #[error(transparent)]
+    Registry(#[from] windows_result::Error),
+}
+
+impl RegistryWriteError {
+    /// The underlying registry error.
+    fn registry_error(&self) -> &windows_result::Error {
+        match self {
+            Self::CreateCompanyKey { err, .. } | Self::Registry(err) => err,
+        }
+    }
+}
+
+/// Whether a registry error is caused by concurrent access and may succeed when retried.
+///
+/// Permanent errors, such as access denied, are not retried.
+fn is_transient_registry_error(err: &windows_result::Error) -> bool {
+    [
+        ERROR_SHARING_VIOLATION,
+        ERROR_LOCK_VIOLATION,
+        ERROR_BUSY,
+        // Another process removed the key while we were writing to it.
+        ERROR_KEY_DELETED,
+        // Another process replaced the `InstallPath` while we were replacing it.
+        ERROR_ALREADY_EXISTS,
+    ]
+    .into_iter()
+    .any(|code| err.code() == HRESULT::from_win32(code))
+}
+
+/// Check that a name can be used for a single registry key.
+///
+/// Backslashes would create nested keys instead, and the registry limits key names to 255
+/// characters.
+fn validate_registry_key_name(name: &str) -> Result<(), &'static str> {
+    if name.is_empty() {
+        return Err("the name is empty");
+    }
+    if name.contains('\\') {
+        return Err("the name contains a path separator");
+    }
+    if name.encode_utf16().count() > MAX_KEY_NAME_LENGTH {
+        return Err("the name is longer than 255 characters");
+    }
+    Ok(())
+}
+
+fn write_registry_entry(
+    root: &RegistryRoot,
+    installation: &ManagedPythonInstallation,
+    metadata: &RegistryMetadata,
+    tag: &str,
+    pointer_width: i32,
+) -> Result<(), RegistryWriteError> {
+    // We currently just overwrite all known keys that differ, without removing prior entries first
+
+    // Similar to using the bin directory in HOME on Unix, we only install for the current user
+    // on Windows, unless provisioning another user's hive.
+    let company = root
+        .create_below_python_key_in_view(COMPANY_KEY, pointer_width)
+        .map_err(|err| RegistryWriteError::CreateCompanyKey {
+            path: format!("{root}\\{}\\{COMPANY_KEY}", python_key()),
+            err,
+        })?;
+    set_value_if_changed(
+        &company,
+        "DisplayName",
+        metadata.company_display_name.as_str(),
+    )?;
+    set_value_if_changed(&company, "SupportUrl", metadata.support_url.as_str())?;
+    set_value_if_changed(&company, REGISTRY_SCHEMA_VALUE, REGISTRY_SCHEMA_VERSION)?;
+
+    // Ex) CPython3.13.1
+    let tag = company.create(tag)?;
+    let display_name = metadata
+        .indirect_display_name()
+        .unwrap_or_else(|| registry_display_name(installation.key(), pointer_width));
+    set_value_if_changed(&tag, "DisplayName", display_name.as_str())?;
+    set_value_if_changed(&tag, "SupportUrl", metadata.support_url.as_str())?;
+    set_value_if_changed(
+        &tag,
+        "Version",
+        installation.key().version().to_string().as_str(),
+    )?;
+    set_value_if_changed(
+        &tag,
+        "SysVersion",
+        installation.key().sys_version().as_str(),
+    )?;
+    set_value_if_changed(
+        &tag,
+        "SysArchitecture",
+        format!("{pointer_width}bit").as_str(),
+    )?;
+    write_download_metadata(&tag, installation)?;
+    // Relate variants, such as the free-threaded build, to the base version they are built from.
+    set_value_if_changed(
+        &tag,
+        VARIANT_VALUE,
+        installation.key().variant().to_string().as_str(),
+    )?;
+    set_value_if_changed(
+        &tag,
+        BASE_VERSION_VALUE,
+        installation.key().version().to_string().as_str(),
+    )?;
+    if metadata.aliases.is_empty() {
+        // Don't keep the aliases of a previous registration.
+        match tag.remove_value(ALIASES_VALUE) {
+            Err(err) if err.code() != ERROR_NOT_FOUND => return Err(err.into()),
+            _ => {}
+        }
+    } else {
+        let existing = tag
+            .get_multi_string(ALIASES_VALUE)
+            .is_ok_and(|existing| existing == metadata.aliases);
+        if !existing {
+            let aliases: Vec<&str> = metadata.aliases.iter().map(String::as_str).collect();
+            tag.set_multi_string(ALIASES_VALUE, &aliases)?;
+        }
+    }
+    if metadata.description {
+        set_value_if_changed(
+            &tag,
+            DESCRIPTION_VALUE,
+            registry_description(installation.key(), installation.url()).as_str(),
+        )?;
+    } else {
+        match tag.remove_value(DESCRIPTION_VALUE) {
+            Err(err) if err.code() != ERROR_NOT_FOUND => return Err(err.into()),
+            _ => {}
+        }
+    }
+
+    let mut install_path_values = vec![
+        ("", Value::from(&registry_path_value(installation.path()))),
+        (
+            "ExecutablePath",
+            Value::from(&registry_path_value(&installation.executable(false))),
+        ),
+    ];
+    let windowed_executable = installation.executable(true);
+    let existing_windowed_executable = tag
+        .open("InstallPath")
+        .and_then(|install_path| read_string_value(&install_path, "WindowedExecutablePath"))
+        .ok()
+        .filter(|existing| !is_same_path(Path::new(existing), &windowed_executable));
+    let preserve = match existing_windowed_executable {
+        Some(existing) if metadata.preserve_windowed_executable_path => {
+            debug!(
+                "Preserving the customized `WindowedExecutablePath` of {}: `{existing}`",
+                installation.key()
+            );
+            true
+        }
+        Some(existing) => {
+            debug!(
+                "Overwriting the `WindowedExecutablePath` of {} (`{existing}`) with `{}`",
+                installation.key(),
+                windowed_executable.display()
+            );
+            false
+        }
+        None => false,
+    };
+    if !preserve {
+        install_path_values.push((
+            "WindowedExecutablePath",
+            Value::from(&registry_path_value(&windowed_executable)),
+        ));
+    }
+    write_install_path(&tag, &install_path_values)?;
+    Ok(())
+}
+
+/// Write the values of a tag that describe where the installation comes from.
+///
+/// Values of a previous build that the installation doesn't have anymore are removed.
+fn write_download_metadata(
+    tag: &Key,
+    installation: &ManagedPythonInstallation,
+) -> windows_result::Result<()> {
+    // Store `python-build-standalone` release
+    for (name, value) in [
+        ("DownloadUrl", installation.url()),
+        ("DownloadSha256", installation.sha256()),
+    ] {
+        if let Some(value) = value {
+            set_value_if_changed(tag, name, value)?;
+        } else {
+            match tag.remove_value(name) {
+                Err(err) if err.code() != ERROR_NOT_FOUND => return Err(err),
+                _ => {}
+            }
+        }
+    }
+    set_value_if_changed(tag, INSTALL_SOURCE_VALUE, INSTALL_SOURCE_UV)
+}
+
+/// Update the values of the registry entry of a managed installation that describe where it
+/// comes from, e.g., after uv replaced the files of a build in place with a rebuild of the same
+/// version.
+///
+/// Unlike [`create_registry_entry`], this only writes `DownloadUrl`, `DownloadSha256` and
+/// [`INSTALL_SOURCE_VALUE`] of the existing tag, and only if they changed. `InstallPath` is not
+/// touched.
+pub fn update_registry_metadata(
+    installation: &ManagedPythonInstallation,
+) -> Result<(), ManagedPep514Error> {
+    let tag = registry_python_tag(installation.key());
+    let path = format!("{}\\{COMPANY_KEY}\\{tag}", python_key());
+    // `create` would add a tag without `InstallPath`, so check that the entry exists first.
+    match CURRENT_USER.open(&path) {
+        Ok(_) => {}
+        Err(err) if err.code() == ERROR_NOT_FOUND => {
+            return Err(ManagedPep514Error::MissingRegistryEntry(
+                installation.key().clone(),
+            ));
+        }
+        Err(err) => return Err(err.into()),
+    }
+    // `open` is read-only.
+    let tag = CURRENT_USER.create(&path)?;
+    let result = write_download_metadata(&tag, installation);
+    invalidate_registry_cache();
+    Ok(result?)
+}
+
+/// The prefix of the keys [`write_install_path`] writes the values to before renaming them to
+/// `InstallPath`, followed by the process id and a counter of the writer, e.g.,
+/// `InstallPath.uv-tmp-1234-0`.
+const INSTALL_PATH_TEMP_PREFIX: &str = "InstallPath.uv-tmp";
+
+/// Write the values of the `InstallPath` of a tag, such that readers never see a partially
+/// written key, even if uv is interrupted.
+///
+/// The registry only has transactions through the deprecated Kernel Transaction Manager, so the
+/// values are written to a temporary key unique to the writer, which then replaces `InstallPath`,
+/// see [`replace_install_path`]. Other values of the existing key are kept. If the existing key
+/// already has the values, nothing is written.
+fn write_install_path(tag: &Key, values: &[(&str, Value)]) -> windows_result::Result<()> {
+    let Some(temp) = stage_install_path(tag, values)? else {
+        return Ok(());
+    };
+    replace_install_path(tag, &temp)
+}
+
+/// Write the values and the other values of the existing `InstallPath` to a new temporary key,
+/// returning its name, or `None` if the existing key already has the values.
+///
+/// Leftovers of interrupted writes are removed.
+fn stage_install_path(
+    tag: &Key,
+    values: &[(&str, Value)],
+) -> windows_result::Result<Option<String>> {
+    static WRITES: atomic::AtomicU64 = atomic::AtomicU64::new(0);
+
+    let existing = match tag.open("InstallPath") {
+        Ok(existing) => Some(existing),
+        Err(err) if err.code() == ERROR_NOT_FOUND => None,
+        Err(err) => return Err(err),
+    };
+    if existing.as_ref().is_some_and(|existing| {
+        values.iter().all(|(name, value)| {
+            existing
+                .get_value(name)
+                .is_ok_and(|existing| existing == *value)
+        })
+    }) {
+        return Ok(None);
+    }
+
+    for name in tag.keys()? {
+        if is_stale_install_path_temp_key(&name) {
+            debug!("Removing the leftovers of an interrupted registry write: `{name}`");
+            remove_subkey_tree(tag, &name)?;
+        }
+    }
+    let name = format!(
+        "{INSTALL_PATH_TEMP_PREFIX}-{}-{}",
+        std::process::id(),
+        WRITES.fetch_add(1, atomic::Ordering::Relaxed)
+    );
+    let temp = tag.create(&name)?;
+    if let Some(existing) = &existing {
+        for (name, value) in existing.values()? {
+            temp.set_value(name, &value)?;
+        }
+    }
+    for (name, value) in values {
+        temp.set_value(name, value)?;
+    }
+    Ok(Some(name))
+}
+
+/// Replace `InstallPath` with the temporary key written by [`stage_install_path`].
+///
+/// Renaming can't replace an existing key, so the existing `InstallPath` is renamed aside first
+/// and removed afterwards. In between, a concurrent scan may briefly miss the entry, but it never
+/// sees a partially written `InstallPath`. If another writer replaces `InstallPath` in between,
+/// the temporary key is removed and the write fails with a transient error, see
+/// [`is_transient_registry_error`], to be retried against the new key.
+fn replace_install_path(tag: &Key, temp: &str) -> windows_result::Result<()> {
+    let replaced = format!("{temp}.old");
+    match rename_subkey(tag, "InstallPath", &replaced) {
+        Err(err) if err.code() != ERROR_NOT_FOUND => {
+            remove_subkey_tree(tag, temp)?;
+            return Err(err);
+        }
+        _ => {}
+    }
+    let result = rename_subkey(tag, temp, "InstallPath");
+    remove_subkey_tree(tag, &replaced)?;
+    match result {
+        Ok(()) => Ok(()),
+        Err(err) => {
+            remove_subkey_tree(tag, temp)?;
+            if tag.open("InstallPath").is_ok() {
+                Err(windows_result::Error::from_hresult(HRESULT::from_win32(
+                    ERROR_ALREADY_EXISTS,
+                )))
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Whether a subkey of a tag is a temporary key of [`stage_install_path`] whose writer isn't
+/// running anymore, including the fixed name used by earlier versions of uv.
+fn is_stale_install_path_temp_key(name: &str) -> bool {
+    let Some(writer) = name.strip_prefix(INSTALL_PATH_TEMP_PREFIX) else {
+        return false;
+    };
+    let pid = writer
+        .strip_prefix('-')
+        .and_then(|writer| writer.split('-').next())
+        .and_then(|pid| pid.parse::<u32>().ok());
+    pid.is_none_or(|pid| !is_process_running(pid))
+}
+
+/// Whether a process with the id is running.
+fn is_process_running(pid: u32) -> bool {
+    if pid == std::process::id() {
+        return true;
+    }
+    // SAFETY: The handle is checked for null and closed after use.
+    #[allow(unsafe_code)]
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            // Processes of other users may not be accessible, but are running.
+            return GetLastError() == ERROR_ACCESS_DENIED;
+        }
+        let mut exit_code = 0;
+        let running = GetExitCodeProcess(handle, &mut exit_code) != 0
+            && u32::try_from(STILL_ACTIVE).is_ok_and(|still_active| exit_code == still_active);
+        CloseHandle(handle);
+        running
+    }
+}
+
+/// Remove a subkey with all its subkeys, if it exists.
+fn remove_subkey_tree(key: &Key, name: &str) -> windows_result::Result<()> {
+    match key.remove_tree(name) {
+        Err(err) if err.code() != ERROR_NOT_FOUND => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Rename a subkey, which `windows-registry` doesn't support.
+fn rename_subkey(key: &Key, name: &str, new_name: &str) -> windows_result::Result<()> {
+    let name = HSTRING::from(name);
+    let new_name = HSTRING::from(new_name);
+    // SAFETY: The key is valid, and both names are null-terminated wide strings that outlive the
+    // call.
+    #[allow(unsafe_code)]
+    let result = unsafe { RegRenameKey(key.as_raw(), name.as_ptr(), new_name.as_ptr()) };
+    if result != ERROR_SUCCESS {
+        return Err(windows_result::Error::from_hresult(HRESULT::from_win32(
+            result,
+        )));
+    }
+    Ok(())
+}
+
+/// Convert a path into a registry value with backslashes as separators, which tools reading the
+/// registry, such as the `py` launcher, expect.
+///
+/// The path is otherwise kept as-is, including unpaired surrogates.
+fn registry_path_value(path: &Path) -> HSTRING {
+    let wide: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .map(|unit| {
+            if unit == u16::from(b'/') {
+                u16::from(b'\\')
+            } else {
+                unit
+            }
+        })
+        .collect();
+    HSTRING::from_wide(&wide)
+}
+
+/// Set a registry value, unless it already has the same type and data.
+///
+/// This makes rewriting an unchanged entry a no-op, which doesn't wake up processes watching the
+/// registry for changes, see [`watch_registry_changes`].
+fn set_value_if_changed(
+    key: &Key,
+    name: &str,
+    value: impl Into<Value>,
+) -> windows_registry::Result<()> {
+    let value = value.into();
+    if key.get_value(name).is_ok_and(|existing| existing == value) {
+        return Ok(());
+    }
+    key.set_value(name, &value)
+}
+
+/// The `DisplayName` of the registry entry of a managed installation, e.g.,
+/// `CPython 3.13.1 (64-bit)` or `CPython 3.13.1 (64-bit, freethreaded)`.
+pub fn registry_display_name(key: &PythonInstallationKey, pointer_width: i32) -> String {
+    match key.variant() {
+        PythonVariant::Default => format!(
+            "{} {} ({pointer_width}-bit)",
+            key.implementation().pretty(),
+            key.version(),
+        ),
+        PythonVariant::Freethreaded => format!(
+            "{} {} ({pointer_width}-bit, freethreaded)",
+            key.implementation().pretty(),
+            key.version(),
+        ),
+    }
+}
+
+/// The [`DESCRIPTION_VALUE`] of the registry entry of a managed installation, e.g.,
+/// `Managed by uv: CPython 3.13.1 from python-build-standalone 20241206`.
+///
+/// The release is read from the download URL, if the installation was downloaded from a
+/// `python-build-standalone` release.
+pub fn registry_description(key: &PythonInstallationKey, url: Option<&str>) -> String {
+    let mut description = format!(
+        "Managed by uv: {} {}",
+        key.implementation().pretty(),
+        key.version()
+    );
+    // Ex) https://github.com/astral-sh/python-build-standalone/releases/download/20241206/...
+    let release = url.and_then(|url| {
+        let (_, rest) = url.split_once("/python-build-standalone/releases/download/")?;
+        rest.split('/').next().filter(|release| !release.is_empty())
+    });
+    if let Some(release) = release {
+        let _ = write!(description, " from python-build-standalone {release}");
+    }
+    description
+}
+
+/// The tag of a managed installation, e.g., `CPython3.13.1` or `CPython3.13.1t`.
+///
+/// The variant suffix ensures that the free-threaded build doesn't overwrite the entry of the
+/// default build of the same version.
+fn registry_python_tag(key: &PythonInstallationKey) -> String {
+    format!(
+        "{}{}{}",
+        key.implementation().pretty(),
+        key.version(),
+        key.variant().suffix()
+    )
+}
+
+/// Remove requested Python entries from the Windows Registry (PEP 514).
+pub fn remove_registry_entry<'a>(
+    installations: impl IntoIterator<Item = &'a ManagedPythonInstallation>,
+    all: bool,
+    errors: &mut Vec<(PythonInstallationKey, anyhow::Error)>,
+) {
+    invalidate_registry_cache();
+    let astral_key = format!("{}\\{COMPANY_KEY}", python_key());
+    if all {
+        debug!("Removing registry key HKCU:\\{}", astral_key);
+        if let Err(err) = CURRENT_USER.remove_tree(&astral_key) {
+            if err.code() == ERROR_NOT_FOUND {
+                debug!("No registry entries to remove, no registry key {astral_key}");
+            } else {
+                warn_user!("Failed to clear registry entries under {astral_key}: {err}");
+            }
+        }
+        return;
+    }
+
+    for installation in installations {
+        let python_tag = registry_python_tag(installation.key());
+        let python_entry = format!("{astral_key}\\{python_tag}");
+        debug!("Removing registry key HKCU:\\{}", python_entry);
+        if let Err(err) = CURRENT_USER.remove_tree(&python_entry) {
+            if err.code() == ERROR_NOT_FOUND {
+                debug!(
+                    "No registry entries to remove for {}, no registry key {}",
+                    installation.key(),
+                    python_entry
+                );
+            } else {
+                errors.push((
+                    installation.key().clone(),
+                    anyhow::Error::new(err)
+                        .context("Failed to clear registry entries under HKCU:\\{python_entry}"),
+                ));
+            }
+        };
+    }
+}
+
+/// Remove Python entries from the Windows Registry (PEP 514) that are not matching any
+/// installation.
+///
+/// The alias of [`register_latest_alias`] is kept, it's updated by calling that function again.
+pub fn remove_orphan_registry_entries(installations: &[ManagedPythonInstallation]) {
+    let keep: HashSet<_> = installations
+        .iter()
+        .map(|installation| registry_python_tag(installation.key()))
+        .chain(std::iter::once(LATEST_TAG.to_string()))
+        .collect();
+    invalidate_registry_cache();
+    let astral_key = format!("{}\\{COMPANY_KEY}", python_key());
+    let key = match CURRENT_USER.open(&astral_key) {
+        Ok(subkeys) => subkeys,
+        Err(err) if err.code() == ERROR_NOT_FOUND => {
+            return;
+        }
+        Err(err) => {
+            // TODO(konsti): We don't have an installation key here.
+            warn_user_once!("Failed to open HKCU:\\{astral_key}: {err}");
+            return;
+        }
+    };
+    // Separate assignment since `keys()` creates a borrow.
+    let subkeys = match key.keys() {
+        Ok(subkeys) => subkeys,
+        Err(err) => {
+            // TODO(konsti): We don't have an installation key here.
+            warn_user_once!("Failed to list subkeys of HKCU:\\{astral_key}: {err}");
+            return;
+        }
+    };
+    for subkey in subkeys {
+        if keep.contains(&subkey) {
+            continue;
+        }
+        let python_entry = format!("{astral_key}\\{subkey}");
+        debug!("Removing orphan registry key HKCU:\\{}", python_entry);
+        if let Err(err) = CURRENT_USER.remove_tree(&python_entry) {
+            // TODO(konsti): We don't have an installation key here.
+            warn_user_once!("Failed to remove orphan registry key HKCU:\\{python_entry}: {err}");
+        };
+    }
+}
+
+/// Check our Python entries in the Windows Registry (PEP 514) for interpreters that don't exist
+/// anymore, e.g., because the installation directory was moved or deleted without uv.
+///
+/// Warns about each stale entry and returns their tags. If `remove` is set, the stale entries are
+/// removed from the registry.
+pub fn verify_managed_registry(remove: bool) -> Vec<String> {
+    let astral_key = format!("{}\\{COMPANY_KEY}", python_key());
+    let key = match CURRENT_USER.open(&astral_key) {
+        Ok(key) => key,
+        Err(err) if err.code() == ERROR_NOT_FOUND => {
+            return Vec::new();
+        }
+        Err(err) => {
+            warn_user_once!("Failed to open HKCU:\\{astral_key}: {err}");
+            return Vec::new();
+        }
+    };
+    let tags = match key.keys() {
+        Ok(tags) => tags,
+        Err(err) => {
+            warn_user_once!("Failed to list subkeys of HKCU:\\{astral_key}: {err}");
+            return Vec::new();
+        }
+    };
+
+    let mut stale = Vec::new();
+    for tag in tags {
+        let python_entry = format!("{astral_key}\\{tag}");
+        let Ok(executable_path) = key
+            .open(format!("{tag}\\InstallPath"))
+            .and_then(|install_path| read_string_value(&install_path, "ExecutablePath"))
+        else {
+            debug!("Skipping registry key without executable HKCU:\\{python_entry}");
+            continue;
+        };
+        if PathBuf::from(&executable_path).is_file() {
+            continue;
+        }
+        if remove {
+            invalidate_registry_cache();
+            warn_user!(
+                "Removing registry entry HKCU:\\{python_entry}, its interpreter \
+                `{executable_path}` doesn't exist anymore"
+            );
+            if let Err(err) = CURRENT_USER.remove_tree(&python_entry) {
+                warn_user_once!("Failed to remove stale registry key HKCU:\\{python_entry}: {err}");
+            }
+        } else {
+            warn_user!(
+                "The registry entry HKCU:\\{python_entry} is stale, its interpreter \
+                `{executable_path}` doesn't exist anymore"
+            );
+        }
+        stale.push(tag);
+    }
+    stale
+}
+
+/// A problem with one of our registry entries, see [`validate_managed_registry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryProblem {
+    /// The tag lacks a value our entries have, e.g., `SysArchitecture`.
+    MissingValue { tag: String, value: &'static str },
+    /// A value of the tag is implausible, e.g., a `SysVersion` that isn't a Python version.
+    InvalidValue {
+        tag: String,
+        value: &'static str,
+        reason: String,
+    },
+    /// The tag key can't be opened, e.g., due to its permissions.
+    UnreadableKey { tag: String, err: String },
+}
+
+impl std::fmt::Display for RegistryProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingValue { tag, value } => {
+                write!(f, "The registry entry `{tag}` has no `{value}`")
+            }
+            Self::InvalidValue { tag, value, reason } => {
+                write!(f, "The `{value}` of the registry entry `{tag}` {reason}")
+            }
+            Self::UnreadableKey { tag, err } => {
+                write!(f, "The registry entry `{tag}` can't be read: {err}")
+            }
+        }
+    }
+}
+
+/// Check that each of our Python entries in the Windows Registry (PEP 514) has the values we
+/// write, with plausible contents, e.g., for a diagnostic command.
+///
+/// Unlike [`verify_managed_registry`], this doesn't modify the registry. Entries with problems can
+/// be rewritten with [`refresh_all_registry_entries`].
+pub fn validate_managed_registry() -> Vec<RegistryProblem> {
+    let astral_key = format!("{}\\{COMPANY_KEY}", python_key());
+    let key = match CURRENT_USER.open(&astral_key) {
+        Ok(key) => key,
+        Err(err) if err.code() == ERROR_NOT_FOUND => {
+            return Vec::new();
+        }
+        Err(err) => {
+            warn_user_once!("Failed to open HKCU:\\{astral_key}: {err}");
+            return Vec::new();
+        }
+    };
+    let tags = match key.keys() {
+        Ok(tags) => tags,
+        Err(err) => {
+            warn_user_once!("Failed to list subkeys of HKCU:\\{astral_key}: {err}");
+            return Vec::new();
+        }
+    };
+
+    let mut problems = Vec::new();
+    for tag in tags {
+        match key.open(&tag) {
+            Ok(tag_key) => problems.extend(validate_managed_entry(&tag, &tag_key)),
+            Err(err) => problems.push(RegistryProblem::UnreadableKey {
+                tag,
+                err: err.to_string(),
+            }),
+        }
+    }
+    problems
+}
+
+/// Check the values of one of our tags, see [`validate_managed_registry`].
+fn validate_managed_entry(tag: &str, tag_key: &Key) -> Vec<RegistryProblem> {
+    let mut problems = Vec::new();
+    let install_path = tag_key.open("InstallPath").ok();
+    let mut read = |key: Option<&Key>, value: &'static str| {
+        let contents = key
+            .and_then(|key| read_string_value(key, value).ok())
+            .filter(|contents| !contents.trim().is_empty());
+        if contents.is_none() {
+            problems.push(RegistryProblem::MissingValue {
+                tag: tag.to_string(),
+                value,
+            });
+        }
+        contents
+    };
+    read(Some(tag_key), "DisplayName");
+    let version = read(Some(tag_key), "Version");
+    let sys_version = read(Some(tag_key), "SysVersion");
+    let sys_architecture = read(Some(tag_key), "SysArchitecture");
+    let executable_path = read(install_path.as_ref(), "ExecutablePath");
+
+    let mut invalid = |value: &'static str, reason: String| {
+        problems.push(RegistryProblem::InvalidValue {
+            tag: tag.to_string(),
+            value,
+            reason,
+        });
+    };
+    let parse = |version: &str| PythonVersion::from_str(version).ok();
+    let version = version.and_then(|version| {
+        let parsed = parse(&version);
+        if parsed.is_none() {
+            invalid("Version", format!("`{version}` is not a Python version"));
+        }
+        parsed
+    });
+    if let Some(sys_version) = sys_version {
+        match parse(&sys_version) {
+            None => invalid(
+                "SysVersion",
+                format!("`{sys_version}` is not a Python version"),
+            ),
+            Some(sys_version) => {
+                if let Some(version) = version.filter(|version| {
+                    (version.major(), version.minor(), version.patch())
+                        != (
+                            sys_version.major(),
+                            sys_version.minor(),
+                            sys_version.patch(),
+                        )
+                }) {
+                    invalid(
+                        "SysVersion",
+                        format!("`{sys_version}` doesn't match the `Version` `{version}`"),
+                    );
+                }
+            }
+        }
+    }
+    if let Some(sys_architecture) = sys_architecture {
+        if !matches!(sys_architecture.as_str(), "32bit" | "64bit") {
+            invalid(
+                "SysArchitecture",
+                format!("`{sys_architecture}` is neither `32bit` nor `64bit`"),
+            );
+        }
+    }
+    if let Some(executable_path) = executable_path {
+        if !Path::new(&executable_path).is_file() {
+            invalid(
+                "ExecutablePath",
+                format!("`{executable_path}` doesn't exist"),
+            );
+        }
+    }
+    problems
+}
+
+/// Compare the archive hashes recorded in our registry entries with the hashes of the downloads
+/// of the installations, returning the installations with a mismatch.
+///
+/// A mismatch means that the registered interpreter isn't the build uv installed, e.g., because
+/// the installation was corrupted or swapped out. Installations without a registry entry or
+/// without a known hash are skipped.
+pub fn verify_registry_sha256<'a>(
+    installations: impl IntoIterator<Item = &'a ManagedPythonInstallation>,
+) -> Vec<PythonInstallationKey> {
+    let mut registry_hashes = Vec::new();
+    let options = RegistryScanOptions::default()
+        .with_roots(vec![RegistryRoot::CurrentUser])
+        .with_allowed_companies(vec![COMPANY_KEY.to_string()]);
+    let result = walk_registry_pythons(&options, |python| {
+        if let Some(sha256) = python.download_sha256 {
+            registry_hashes.push((python.path, sha256));
+        }
+        ControlFlow::Continue(())
+    });
+    if let Err(err) = result {
+        warn_user_once!("Failed to read registry entries for verification: {err}");
+        return Vec::new();
+    }
+
+    let mut mismatches = Vec::new();
+    for installation in installations {
+        let executable = installation.executable(false);
+        let Some((_, registry_sha256)) =
+            registry_hashes.iter().find(|(path, _)| *path == executable)
+        else {
+            continue;
+        };
+        // Installations read from disk don't know their download, so we fall back to the hash of
+        // the download with the same key.
+        let Some(expected_sha256) = installation.sha256().or_else(|| {
+            ManagedPythonDownload::iter_all()
+                .find(|download| download.key() == installation.key())
+                .and_then(ManagedPythonDownload::sha256)
+        }) else {
+            debug!(
+                "No known SHA256 for {}, skipping registry verification",
+                installation.key()
+            );
+            continue;
+        };
+        if !registry_sha256.eq_ignore_ascii_case(expected_sha256) {
+            warn_user!(
+                "The registry entry for {} records the SHA256 `{registry_sha256}`, \
+                but the installation was downloaded with SHA256 `{expected_sha256}`",
+                installation.key()
+            );
+            mismatches.push(installation.key().clone());
+        }
+    }
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        registry_pythons, registry_pythons_with, with_python_key, RegistryRoot, RegistryScanOptions,
+    };
+    use crate::managed::ManagedPythonInstallation;
+    use crate::{PythonInstallationKey, PythonVersion};
+    use proptest::prelude::*;
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+    use windows_registry::CURRENT_USER;
+
+    /// A sandbox below `HKCU` that is unique to a single test.
+    struct TestRegistry {
+        python_key: String,
+    }
+
+    impl TestRegistry {
+        fn new(name: &str) -> Self {
+            let python_key = format!(r"Software\uv-test\{}\{name}\Python", std::process::id());
+            // Start from a clean slate in case a previous run was interrupted.
+            let _ = CURRENT_USER.remove_tree(&python_key);
+            Self { python_key }
+        }
+
+        /// Register a Python with the given company, tag, version and executable.
+        fn register(
+            &self,
+            company: &str,
+            tag: &str,
+            sys_version: &str,
+            executable: &str,
+        ) -> anyhow::Result<()> {
+            let tag = CURRENT_USER.create(format!(r"{}\{company}\{tag}", self.python_key))?;
+            tag.set_string("SysVersion", sys_version)?;
+            tag.create("InstallPath")?
+                .set_string("ExecutablePath", executable)?;
+            Ok(())
+        }
+
+        /// Import a fixture from `test-data/registry` below the sandbox key.
+        ///
+        /// Fixtures use the format of [`super::dump_registry_pythons_json`] for a single root, so
+        /// dumps attached to bug reports can be added as fixtures.
+        fn import_fixture(&self, fixture: &str) -> anyhow::Result<()> {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("test-data")
+                .join("registry")
+                .join(format!("{fixture}.json"));
+            let dump: serde_json::Value = serde_json::from_str(&fs_err::read_to_string(path)?)?;
+            Ok(super::import_registry_key(
+                &CURRENT_USER.create(&self.python_key)?,
+                &dump,
+            )?)
+        }
+
+        /// Register a uv-managed installation with the metadata, like `uv python install` does.
+        fn register_installation(
+            &self,
+            installation: &ManagedPythonInstallation,
+            metadata: &super::RegistryMetadata,
+        ) -> anyhow::Result<()> {
+            let mut errors = Vec::new();
+            self.run(|| {
+                super::create_registry_entry_in(
+                    &RegistryRoot::CurrentUser,
+                    installation,
+                    metadata,
+                    &mut errors,
+                )
+            })?;
+            assert!(errors.is_empty());
+            Ok(())
+        }
+
+        fn run<T>(&self, f: impl FnOnce() -> T) -> T {
+            with_python_key(&self.python_key, f)
+        }
+    }
+
+    impl Drop for TestRegistry {
+        fn drop(&mut self) {
+            let _ = CURRENT_USER.remove_tree(&self.python_key);
+        }
+    }
+
+    /// An installation in a directory that doesn't exist, for writing registry entries without
+    /// installing a Python.
+    fn test_installation(name: &str) -> anyhow::Result<ManagedPythonInstallation> {
+        Ok(ManagedPythonInstallation::from_path(
+            PathBuf::from(r"C:\uv-test").join(name),
+        )?)
+    }
+
+    /// The installation used by most tests, see [`test_installation`].
+    fn cpython312() -> anyhow::Result<ManagedPythonInstallation> {
+        test_installation("cpython-3.12.8-windows-x86_64-none")
+    }
+
+    #[test]
+    fn sandboxed_python_key() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("sandboxed_python_key");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example\python.exe")?;
+
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(pythons.len(), 1);
+        assert_eq!(pythons[0].path, PathBuf::from(r"C:\Example\python.exe"));
+        assert_eq!(pythons[0].version.as_ref().unwrap().to_string(), "3.12");
+        Ok(())
+    }
+
+    #[test]
+    fn scan_options() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("scan_options");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+        registry.register("ExampleCorp", "3.8", "3.8", r"C:\Example38\python.exe")?;
+        registry.register("OtherCorp", "3.13", "3.13", r"C:\Other313\python.exe")?;
+
+        let paths = |options: RegistryScanOptions| -> anyhow::Result<Vec<PathBuf>> {
+            let pythons = registry.run(|| registry_pythons_with(&options))?;
+            Ok(pythons.into_iter().map(|python| python.path).collect())
+        };
+
+        assert_eq!(
+            paths(
+                RegistryScanOptions::default()
+                    .with_allowed_companies(vec!["examplecorp".to_string()])
+            )?,
+            [
+                PathBuf::from(r"C:\Example312\python.exe"),
+                PathBuf::from(r"C:\Example38\python.exe"),
+            ]
+        );
+        assert_eq!(
+            paths(
+                RegistryScanOptions::default()
+                    .with_denied_companies(vec!["OtherCorp".to_string()])
+                    .with_min_version(Some(PythonVersion::from_str("3.9").unwrap()))
+            )?,
+            [PathBuf::from(r"C:\Example312\python.exe")]
+        );
+        assert!(paths(
+            RegistryScanOptions::default().with_roots(vec![RegistryRoot::LocalMachine])
+        )?
+        .is_empty());
+
+        // Python 2 is skipped unless requested.
+        registry.register("ExampleCorp", "2.7", "2.7", r"C:\Example27\python.exe")?;
+        let python2 = PathBuf::from(r"C:\Example27\python.exe");
+        assert!(!paths(RegistryScanOptions::default())?.contains(&python2));
+        assert!(
+            paths(RegistryScanOptions::default().with_include_python2(true))?.contains(&python2)
+        );
+
+        // Pythons of an unknown implementation are kept.
+        registry.register("PyPy", "3.10", "3.10", r"C:\PyPy310\pypy3.exe")?;
+        registry.register("PythonCore", "3.11", "3.11", r"C:\Python311\python.exe")?;
+        let implementations = paths(
+            RegistryScanOptions::default()
+                .with_implementations(vec![crate::ImplementationName::CPython]),
+        )?;
+        assert!(implementations.contains(&PathBuf::from(r"C:\Python311\python.exe")));
+        assert!(implementations.contains(&PathBuf::from(r"C:\Example312\python.exe")));
+        assert!(!implementations.contains(&PathBuf::from(r"C:\PyPy310\pypy3.exe")));
+
+        // Only entries with a readable version in strict mode.
+        registry.register(
+            "ExampleCorp",
+            "Custom",
+            "unknown",
+            r"C:\ExampleCustom\python.exe",
+        )?;
+        let custom = PathBuf::from(r"C:\ExampleCustom\python.exe");
+        assert!(paths(RegistryScanOptions::default())?.contains(&custom));
+        assert!(!paths(RegistryScanOptions::default().with_strict(true))?.contains(&custom));
+
+        // Only entries with an existing executable without broken entries.
+        let exe = std::env::current_exe()?;
+        registry.register("OtherCorp", "3.14", "3.14", exe.to_str().unwrap())?;
+        assert_eq!(
+            paths(RegistryScanOptions::default().with_include_broken(false))?,
+            [exe]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn has_registry_pythons() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("has_registry_pythons");
+        assert!(!registry.run(super::has_registry_pythons));
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example\python.exe")?;
+        assert!(registry.run(super::has_registry_pythons));
+        Ok(())
+    }
+
+    #[test]
+    fn display_name() {
+        let key = PythonInstallationKey::from_str("cpython-3.13.1-windows-x86_64-none").unwrap();
+        assert_eq!(
+            super::registry_display_name(&key, 64),
+            "CPython 3.13.1 (64-bit)"
+        );
+        assert_eq!(super::registry_python_tag(&key), "CPython3.13.1");
+
+        let key = PythonInstallationKey::from_str("cpython-3.13.1+freethreaded-windows-x86-none")
+            .unwrap();
+        assert_eq!(
+            super::registry_display_name(&key, 32),
+            "CPython 3.13.1 (32-bit, freethreaded)"
+        );
+        assert_eq!(super::registry_python_tag(&key), "CPython3.13.1t");
+    }
+
+    #[test]
+    fn key_name_validation() {
+        assert!(super::validate_registry_key_name("CPython3.13.1").is_ok());
+        assert!(super::validate_registry_key_name("").is_err());
+        assert!(super::validate_registry_key_name(r"CPython\3.13.1").is_err());
+        assert!(super::validate_registry_key_name(&"a".repeat(255)).is_ok());
+        assert!(super::validate_registry_key_name(&"a".repeat(256)).is_err());
+    }
+
+    #[test]
+    fn dump_registry_pythons_json() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("dump_registry_pythons_json");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example\python.exe")?;
+
+        let dump: serde_json::Value =
+            serde_json::from_str(&registry.run(super::dump_registry_pythons_json))?;
+        let tag = &dump["roots"]["HKCU"]["64-bit"]["keys"]["ExampleCorp"]["keys"]["3.12"];
+        assert_eq!(
+            tag["values"]["SysVersion"],
+            serde_json::json!({ "type": "REG_SZ", "data": "3.12" })
+        );
+        assert_eq!(
+            tag["keys"]["InstallPath"]["values"]["ExecutablePath"]["data"],
+            r"C:\Example\python.exe"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn executable_path_on_tag() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("executable_path_on_tag");
+        let tag = CURRENT_USER.create(format!(r"{}\ExampleCorp\3.12", registry.python_key))?;
+        tag.set_string("SysVersion", "3.12")?;
+        tag.set_string("ExecutablePath", r"C:\Fallback\python.exe")?;
+
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(pythons.len(), 1);
+        assert_eq!(pythons[0].path, PathBuf::from(r"C:\Fallback\python.exe"));
+
+        // The conforming layout takes precedence.
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example\python.exe")?;
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(pythons.len(), 1);
+        assert_eq!(pythons[0].path, PathBuf::from(r"C:\Example\python.exe"));
+        Ok(())
+    }
+
+    #[test]
+    fn verify_managed_registry() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("verify_managed_registry");
+        let existing = std::env::current_exe()?;
+        registry.register(
+            crate::COMPANY_KEY,
+            "CPython3.12.0",
+            "3.12",
+            existing.to_str().unwrap(),
+        )?;
+        registry.register(
+            crate::COMPANY_KEY,
+            "CPython3.11.0",
+            "3.11",
+            r"C:\uv-test-missing\python.exe",
+        )?;
+
+        assert_eq!(
+            registry.run(|| super::verify_managed_registry(false)),
+            ["CPython3.11.0"]
+        );
+        assert_eq!(registry.run(registry_pythons)?.len(), 2);
+
+        assert_eq!(
+            registry.run(|| super::verify_managed_registry(true)),
+            ["CPython3.11.0"]
+        );
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(pythons.len(), 1);
+        assert_eq!(pythons[0].path, existing);
+        Ok(())
+    }
+
+    #[test]
+    fn verify_registry_sha256() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("verify_registry_sha256");
+        let installation = cpython312()?;
+        let executable = installation.executable(false);
+        let sha256 = "8a4e9e748eeee7ae71048a108a55a9bac48f8bedf9dff413a7c87744f0408ef1";
+        registry.register(
+            crate::COMPANY_KEY,
+            "CPython3.12.8",
+            "3.12",
+            executable.to_str().unwrap(),
+        )?;
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example\python.exe")?;
+        for tag in [
+            format!(r"{}\CPython3.12.8", crate::COMPANY_KEY),
+            r"ExampleCorp\3.12".to_string(),
+        ] {
+            CURRENT_USER
+                .create(format!(r"{}\{tag}", registry.python_key))?
+                .set_string("DownloadSha256", sha256)?;
+        }
+
+        // Only our own entries carry a meaningful hash.
+        let pythons = registry.run(registry_pythons)?;
+        let hash_of = |path: &PathBuf| {
+            pythons
+                .iter()
+                .find(|python| &python.path == path)
+                .unwrap()
+                .download_sha256
+                .clone()
+        };
+        assert_eq!(hash_of(&executable).as_deref(), Some(sha256));
+        assert_eq!(hash_of(&PathBuf::from(r"C:\Example\python.exe")), None);
+
+        assert!(registry
+            .run(|| super::verify_registry_sha256([&installation]))
+            .is_empty());
+        CURRENT_USER
+            .create(format!(
+                r"{}\{}\CPython3.12.8",
+                registry.python_key,
+                crate::COMPANY_KEY
+            ))?
+            .set_string("DownloadSha256", &"0".repeat(64))?;
+        assert_eq!(
+            registry.run(|| super::verify_registry_sha256([&installation])),
+            [installation.key().clone()]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn registry_metadata() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("registry_metadata");
+        let installation = cpython312()?;
+        let metadata = super::RegistryMetadata::default()
+            .with_company_display_name("Example Corp".to_string())
+            .with_support_url("https://example.com/support".to_string());
+        registry.register_installation(&installation, &metadata)?;
+
+        let company =
+            CURRENT_USER.open(format!(r"{}\{}", registry.python_key, crate::COMPANY_KEY))?;
+        assert_eq!(company.get_string("DisplayName")?, "Example Corp");
+        assert_eq!(
+            company.get_string("SupportUrl")?,
+            "https://example.com/support"
+        );
+        assert_eq!(
+            company.open("CPython3.12.8")?.get_string("SupportUrl")?,
+            "https://example.com/support"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn registry_companies() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("registry_companies");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+        registry.register("ExampleCorp", "3.13", "3.13", r"C:\Example313\python.exe")?;
+        registry.register("PyLauncher", "3.13", "3.13", r"C:\Launcher\python.exe")?;
+        CURRENT_USER
+            .create(format!(r"{}\ExampleCorp", registry.python_key))?
+            .set_string("DisplayName", "Example Corp")?;
+
+        let companies = registry.run(super::registry_companies);
+        let companies: Vec<_> = companies
+            .iter()
+            .filter(|company| company.root == RegistryRoot::CurrentUser)
+            .collect();
+        assert_eq!(companies.len(), 1);
+        assert_eq!(companies[0].name, "ExampleCorp");
+        assert_eq!(companies[0].display_name.as_deref(), Some("Example Corp"));
+        assert_eq!(companies[0].tag_count, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn scan_timeout() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("scan_timeout");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+        registry.register("ExampleCorp", "3.13", "3.13", r"C:\Example313\python.exe")?;
+
+        let options = RegistryScanOptions::default()
+            .with_roots(vec![RegistryRoot::CurrentUser])
+            .with_timeout(Some(std::time::Duration::from_secs(60)));
+        let pythons = registry.run(|| registry_pythons_with(&options))?;
+        assert_eq!(
+            pythons
+                .into_iter()
+                .map(|python| python.path)
+                .collect::<Vec<_>>(),
+            [
+                PathBuf::from(r"C:\Example313\python.exe"),
+                PathBuf::from(r"C:\Example312\python.exe"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scan_timeout_exceeded() -> anyhow::Result<()> {
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let python = |path: &str| super::WindowsPython::new(PathBuf::from(path), None);
+        let (release, released) = mpsc::channel::<()>();
+        let (stopped, is_stopped) = mpsc::channel();
+        // The scan stalls after the first entry until the test releases it.
+        let pythons = super::collect_with_timeout(Duration::from_secs(1), move |visit| {
+            let _ = visit(python(r"C:\Example312\python.exe"));
+            released.recv().unwrap();
+            stopped
+                .send(visit(python(r"C:\Example313\python.exe")).is_break())
+                .unwrap();
+            Ok(())
+        })?;
+        assert_eq!(
+            pythons
+                .into_iter()
+                .map(|python| python.path)
+                .collect::<Vec<_>>(),
+            [PathBuf::from(r"C:\Example312\python.exe")]
+        );
+
+        // The abandoned scan stops at the next entry.
+        release.send(())?;
+        assert!(is_stopped.recv()?);
+        Ok(())
+    }
+
+    #[test]
+    fn arch_hints() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("arch_hints");
+        let entries = [
+            ("PythonCore", "3.12-arm64", r"C:\Arm64\python.exe", "64bit"),
+            ("PythonCore", "3.12", r"C:\X64\python.exe", "64bit"),
+            ("PythonCore", "3.12-32", r"C:\X86\python.exe", "32bit"),
+            ("ExampleCorp", "3.12", r"C:\Unknown\python.exe", "64bit"),
+            (
+                crate::COMPANY_KEY,
+                "CPython3.12.8",
+                r"C:\uv\cpython-3.12.8-windows-aarch64-none\python.exe",
+                "64bit",
+            ),
+        ];
+        for (company, tag, executable, sys_architecture) in entries {
+            registry.register(company, tag, "3.12", executable)?;
+            CURRENT_USER
+                .create(format!(r"{}\{company}\{tag}", registry.python_key))?
+                .set_string("SysArchitecture", sys_architecture)?;
+        }
+
+        // Without `SysArchitecture`, the `DisplayName` is used.
+        registry.register("OtherCorp", "3.12", "3.12", r"C:\Other\python.exe")?;
+        CURRENT_USER
+            .create(format!(r"{}\OtherCorp\3.12", registry.python_key))?
+            .set_string("DisplayName", "Other Python 3.12 (32-bit)")?;
+
+        let pythons = registry.run(registry_pythons)?;
+        let arch_of = |path: &str| {
+            pythons
+                .iter()
+                .find(|python| python.path == PathBuf::from(path))
+                .unwrap()
+                .arch
+                .map(|arch| arch.to_string())
+        };
+        assert_eq!(arch_of(r"C:\Arm64\python.exe").as_deref(), Some("aarch64"));
+        assert_eq!(arch_of(r"C:\X64\python.exe").as_deref(), Some("x86_64"));
+        assert_eq!(arch_of(r"C:\X86\python.exe").as_deref(), Some("x86"));
+        assert_eq!(arch_of(r"C:\Unknown\python.exe"), None);
+        assert_eq!(arch_of(r"C:\Other\python.exe").as_deref(), Some("x86"));
+        assert_eq!(
+            arch_of(r"C:\uv\cpython-3.12.8-windows-aarch64-none\python.exe").as_deref(),
+            Some("aarch64")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn path_order_ignores_casing() {
+        use std::cmp::Ordering;
+        use std::path::Path;
+
+        assert_eq!(
+            super::cmp_paths(Path::new(r"C:\b\python.exe"), Path::new(r"C:\A\python.exe")),
+            Ordering::Greater
+        );
+        assert_eq!(
+            super::cmp_paths(Path::new(r"c:\a\python.exe"), Path::new(r"C:\B\python.exe")),
+            Ordering::Less
+        );
+        assert_eq!(
+            super::cmp_paths(Path::new(r"C:\A\python.exe"), Path::new(r"C:\a\python.exe")),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn registry_cache() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("registry_cache");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+        let count =
+            || -> anyhow::Result<usize> { Ok(registry.run(super::cached_registry_pythons)?.len()) };
+        super::invalidate_registry_cache();
+        let initial = count()?;
+        assert_eq!(count()?, initial);
+
+        // Other tests may invalidate the cache concurrently, so we can only check that changes
+        // are visible after invalidating.
+        registry.register("ExampleCorp", "3.13", "3.13", r"C:\Example313\python.exe")?;
+        super::invalidate_registry_cache();
+        assert_eq!(count()?, initial + 1);
+        Ok(())
+    }
+
+    #[test]
+    fn tag_pointer_width() -> anyhow::Result<()> {
+        use target_lexicon::PointerWidth;
+
+        assert_eq!(
+            super::parse_tag_pointer_width("3.12-32"),
+            Some(PointerWidth::U32)
+        );
+        assert_eq!(
+            super::parse_tag_pointer_width("3.12-64"),
+            Some(PointerWidth::U64)
+        );
+        assert_eq!(
+            super::parse_tag_pointer_width("3.12-ARM64"),
+            Some(PointerWidth::U64)
+        );
+        assert_eq!(super::parse_tag_pointer_width("3.12"), None);
+        assert_eq!(super::parse_tag_pointer_width("3.12-dev"), None);
+
+        let registry = TestRegistry::new("tag_pointer_width");
+        registry.register(
+            "PythonCore",
+            "3.11-32",
+            "3.11",
+            r"C:\Python311-32\python.exe",
+        )?;
+        // An explicit `SysArchitecture` takes precedence over the tag.
+        registry.register("PythonCore", "3.10-32", "3.10", r"C:\Python310\python.exe")?;
+        CURRENT_USER
+            .create(format!(r"{}\PythonCore\3.10-32", registry.python_key))?
+            .set_string("SysArchitecture", "64bit")?;
+
+        let pythons = registry.run(registry_pythons)?;
+        let python_of = |path: &str| {
+            pythons
+                .iter()
+                .find(|python| python.path == PathBuf::from(path))
+                .unwrap()
+        };
+        let python = python_of(r"C:\Python311-32\python.exe");
+        assert_eq!(python.pointer_width, Some(PointerWidth::U32));
+        assert_eq!(
+            python.arch.map(|arch| arch.to_string()).as_deref(),
+            Some("x86")
+        );
+        assert_eq!(
+            python_of(r"C:\Python310\python.exe").pointer_width,
+            Some(PointerWidth::U64)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn display_name_pointer_width() {
+        use target_lexicon::PointerWidth;
+
+        assert_eq!(
+            super::parse_display_name_pointer_width("Example Python 3.12 (64-bit)"),
+            Some(PointerWidth::U64)
+        );
+        assert_eq!(
+            super::parse_display_name_pointer_width("CPython 3.13.1 (32-bit, freethreaded)"),
+            Some(PointerWidth::U32)
+        );
+        assert_eq!(
+            super::parse_display_name_pointer_width("Example Python (64-bit) 3.12"),
+            None
+        );
+        assert_eq!(
+            super::parse_display_name_pointer_width("Example Python 3.12 (beta)"),
+            None
+        );
+        assert_eq!(
+            super::parse_display_name_pointer_width("Example Python 3.12"),
+            None
+        );
+    }
+
+    #[test]
+    fn version_request_matching() {
+        use crate::{PythonVariant, VersionRequest};
+
+        let python = |version: Option<&str>| {
+            super::WindowsPython::new(
+                PathBuf::from(r"C:\Example\python.exe"),
+                version.map(|version| PythonVersion::from_str(version).unwrap()),
+            )
+        };
+        let request_312 = VersionRequest::MajorMinor(3, 12, PythonVariant::Default);
+        let request_3125 = VersionRequest::MajorMinorPatch(3, 12, 5, PythonVariant::Default);
+
+        assert_eq!(python(Some("3.12.5")).major_minor(), Some((3, 12)));
+        assert_eq!(python(None).major_minor(), None);
+
+        assert!(python(Some("3.12.5")).matches_version_request(&request_312, false));
+        assert!(python(Some("3.12.5")).matches_version_request(&request_3125, false));
+        assert!(!python(Some("3.11.5")).matches_version_request(&request_312, false));
+        // Without a patch version, only the minor version is compared.
+        assert!(python(Some("3.12")).matches_version_request(&request_3125, false));
+        // Entries without a version only match unconstrained requests.
+        assert!(!python(None).matches_version_request(&request_312, false));
+        assert!(python(None).matches_version_request(&VersionRequest::Any, false));
+
+        // Pre-releases need to be allowed or requested explicitly.
+        let request_313 = VersionRequest::MajorMinor(3, 13, PythonVariant::Default);
+        let request_313rc1 = VersionRequest::from_str("3.13rc1").unwrap();
+        assert!(!python(Some("3.13.0rc1")).matches_version_request(&request_313, false));
+        assert!(python(Some("3.13.0rc1")).matches_version_request(&request_313, true));
+        assert!(python(Some("3.13.0rc1")).matches_version_request(&request_313rc1, false));
+    }
+
+    #[test]
+    fn prerelease_version() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("prerelease_version");
+        registry.register("ExampleCorp", "3.13", "3.13", r"C:\Example313\python.exe")?;
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+        for (tag, version) in [("3.13", "3.13.0rc1"), ("3.12", "3.11.0rc1")] {
+            CURRENT_USER
+                .create(format!(r"{}\ExampleCorp\{tag}", registry.python_key))?
+                .set_string("Version", version)?;
+        }
+
+        let pythons = registry.run(registry_pythons)?;
+        let versions: Vec<_> = pythons
+            .iter()
+            .map(|python| python.version.as_ref().unwrap().to_string())
+            .collect();
+        // A pre-release `Version` of a different minor version is ignored.
+        assert_eq!(versions, ["3.13.0rc1", "3.12"]);
+        Ok(())
+    }
+
+    #[test]
+    fn windowed_path() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("windowed_path");
+        let entries = [
+            ("3.12", r"C:\Gui\python.exe", r"C:\Gui\pythonw.exe"),
+            ("3.13", r"C:\NoGui\python.exe", r"c:/nogui/PYTHON.EXE"),
+        ];
+        for (tag, executable, windowed) in entries {
+            registry.register("ExampleCorp", tag, tag, executable)?;
+            CURRENT_USER
+                .create(format!(
+                    r"{}\ExampleCorp\{tag}\InstallPath",
+                    registry.python_key
+                ))?
+                .set_string("WindowedExecutablePath", windowed)?;
+        }
+
+        let pythons = registry.run(registry_pythons)?;
+        let windowed_of = |path: &str| {
+            pythons
+                .iter()
+                .find(|python| python.path == PathBuf::from(path))
+                .unwrap()
+                .windowed_path
+                .clone()
+        };
+        assert_eq!(
+            windowed_of(r"C:\Gui\python.exe"),
+            Some(PathBuf::from(r"C:\Gui\pythonw.exe"))
+        );
+        assert_eq!(windowed_of(r"C:\NoGui\python.exe"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn registry_aliases() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("registry_aliases");
+        let installation = cpython312()?;
+
+        registry.register_installation(
+            &installation,
+            &super::RegistryMetadata::default().with_aliases(vec!["latest".to_string()]),
+        )?;
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(pythons[0].aliases, ["latest"]);
+        assert!(pythons[0].has_alias("Latest"));
+
+        // Registering again without aliases removes them.
+        registry.register_installation(&installation, &super::RegistryMetadata::default())?;
+        let pythons = registry.run(registry_pythons)?;
+        assert!(pythons[0].aliases.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn managed_and_external_pythons() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("managed_and_external_pythons");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example\python.exe")?;
+        registry.register(
+            &crate::COMPANY_KEY.to_uppercase(),
+            "CPython3.13.1",
+            "3.13",
+            r"C:\Managed\python.exe",
+        )?;
+
+        let paths = |pythons: Vec<super::WindowsPython>| -> Vec<PathBuf> {
+            pythons
+                .into_iter()
+                .map(|python| python.path)
+                .filter(|path| path.starts_with(r"C:\Example") || path.starts_with(r"C:\Managed"))
+                .collect()
+        };
+        assert_eq!(
+            paths(registry.run(super::managed_registry_pythons)?),
+            [PathBuf::from(r"C:\Managed\python.exe")]
+        );
+        assert_eq!(
+            paths(registry.run(super::external_registry_pythons)?),
+            [PathBuf::from(r"C:\Example\python.exe")]
+        );
+
+        // The company is compared case-insensitively.
+        let pythons = registry.run(registry_pythons)?;
+        let is_managed = |path: &str| {
+            pythons
+                .iter()
+                .find(|python| python.path == PathBuf::from(path))
+                .unwrap()
+                .is_managed()
+        };
+        assert!(is_managed(r"C:\Managed\python.exe"));
+        assert!(!is_managed(r"C:\Example\python.exe"));
+        Ok(())
+    }
+
+    #[test]
+    fn preserve_windowed_executable_path() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("preserve_windowed_executable_path");
+        let installation = cpython312()?;
+        let install_path = format!(
+            r"{}\{}\CPython3.12.8\InstallPath",
+            registry.python_key,
+            crate::COMPANY_KEY
+        );
+        let windowed = || -> anyhow::Result<String> {
+            Ok(CURRENT_USER
+                .open(&install_path)?
+                .get_string("WindowedExecutablePath")?)
+        };
+
+        registry.register_installation(&installation, &super::RegistryMetadata::default())?;
+        CURRENT_USER
+            .create(&install_path)?
+            .set_string("WindowedExecutablePath", r"C:\Custom\pythonw.exe")?;
+        registry.register_installation(
+            &installation,
+            &super::RegistryMetadata::default().with_preserve_windowed_executable_path(true),
+        )?;
+        assert_eq!(windowed()?, r"C:\Custom\pythonw.exe");
+
+        // By default, the value is overwritten.
+        registry.register_installation(&installation, &super::RegistryMetadata::default())?;
+        assert_eq!(PathBuf::from(windowed()?), installation.executable(true));
+        Ok(())
+    }
+
+    /// The path, version and architecture of a Python found in the registry.
+    type Summary = (String, Option<String>, Option<String>);
+
+    /// Scan the fixtures and summarize each Python found.
+    fn scan_fixtures(name: &str, fixtures: &[&str]) -> anyhow::Result<Vec<Summary>> {
+        let registry = TestRegistry::new(name);
+        for fixture in fixtures {
+            registry.import_fixture(fixture)?;
+        }
+        let options = RegistryScanOptions::default().with_roots(vec![RegistryRoot::CurrentUser]);
+        let pythons = registry.run(|| registry_pythons_with(&options))?;
+        Ok(pythons
+            .into_iter()
+            .map(|python| {
+                (
+                    python.path.display().to_string(),
+                    python.version.map(|version| version.to_string()),
+                    python.arch.map(|arch| arch.to_string()),
+                )
+            })
+            .collect())
+    }
+
+    /// Build the expected summary of [`scan_fixtures`].
+    fn expected(pythons: &[(&str, Option<&str>, Option<&str>)]) -> Vec<Summary> {
+        pythons
+            .iter()
+            .map(|(path, version, arch)| {
+                (
+                    (*path).to_string(),
+                    version.map(ToString::to_string),
+                    arch.map(ToString::to_string),
+                )
+            })
+            .collect()
+    }
+
+    const PYTHON_ORG: &[(&str, Option<&str>, Option<&str>)] = &[
+        (
+            r"C:\Users\Example\AppData\Local\Programs\Python\Python313\python.exe",
+            Some("3.13.1"),
+            Some("x86_64"),
+        ),
+        (
+            r"C:\Users\Example\AppData\Local\Programs\Python\Python313\python3.13t.exe",
+            Some("3.13.1"),
+            Some("x86_64"),
+        ),
+        (
+            r"C:\Users\Example\AppData\Local\Programs\Python\Python312-32\python.exe",
+            Some("3.12.4"),
+            Some("x86"),
+        ),
+        (
+            r"C:\Users\Example\AppData\Local\Programs\Python\Python312-arm64\python.exe",
+            Some("3.12.4"),
+            Some("aarch64"),
+        ),
+        (
+            r"C:\Users\Example\AppData\Local\Programs\Python\Python312\python.exe",
+            Some("3.12.4"),
+            Some("x86_64"),
+        ),
+        (
+            r"C:\Users\Example\AppData\Local\Programs\Python\Python311\python.exe",
+            Some("3.11.9"),
+            Some("x86_64"),
+        ),
+        (
+            r"C:\Users\Example\AppData\Local\Programs\Python\Python310\python.exe",
+            Some("3.10.11"),
+            Some("x86_64"),
+        ),
+        (
+            r"C:\Users\Example\AppData\Local\Programs\Python\Python39\python.exe",
+            Some("3.9.13"),
+            Some("x86_64"),
+        ),
+        (
+            r"C:\Users\Example\AppData\Local\Programs\Python\Python38\python.exe",
+            Some("3.8.10"),
+            Some("x86_64"),
+        ),
+    ];
+
+    #[test]
+    fn fixture_python_org() -> anyhow::Result<()> {
+        assert_eq!(
+            scan_fixtures("fixture_python_org", &["python-org"])?,
+            expected(PYTHON_ORG)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fixture_conda() -> anyhow::Result<()> {
+        assert_eq!(
+            scan_fixtures("fixture_conda", &["conda"])?,
+            expected(&[
+                (r"C:\Users\Example\anaconda3\python.exe", Some("3.12"), None),
+                (
+                    r"C:\Users\Example\miniconda3\python.exe",
+                    Some("3.11"),
+                    None
+                ),
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fixture_pypy() -> anyhow::Result<()> {
+        assert_eq!(
+            scan_fixtures("fixture_pypy", &["pypy"])?,
+            expected(&[(
+                r"C:\pypy3.10-v7.3.17-win64\pypy3.exe",
+                Some("3.10.14"),
+                None
+            )])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fixture_malformed() -> anyhow::Result<()> {
+        // The entry without an executable is skipped, entries without a readable version are
+        // sorted last.
+        assert_eq!(
+            scan_fixtures("fixture_malformed", &["malformed"])?,
+            expected(&[
+                (r"C:\Broken\FlatLayout\python.exe", Some("3.10"), None),
+                (r"C:\Broken\BadVersion\python.exe", None, None),
+                (r"C:\Broken\NumericVersion\python.exe", None, None),
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fixture_combined() -> anyhow::Result<()> {
+        let mut pythons = expected(&PYTHON_ORG[..2]);
+        pythons.extend(expected(&PYTHON_ORG[2..5]));
+        pythons.extend(expected(&[(
+            r"C:\Users\Example\anaconda3\python.exe",
+            Some("3.12"),
+            None,
+        )]));
+        pythons.extend(expected(&PYTHON_ORG[5..6]));
+        pythons.extend(expected(&[
+            (
+                r"C:\Users\Example\miniconda3\python.exe",
+                Some("3.11"),
+                None,
+            ),
+            (
+                r"C:\pypy3.10-v7.3.17-win64\pypy3.exe",
+                Some("3.10.14"),
+                None,
+            ),
+        ]));
+        pythons.extend(expected(&PYTHON_ORG[6..7]));
+        pythons.extend(expected(&[(
+            r"C:\Broken\FlatLayout\python.exe",
+            Some("3.10"),
+            None,
+        )]));
+        pythons.extend(expected(&PYTHON_ORG[7..]));
+        pythons.extend(expected(&[
+            (r"C:\Broken\BadVersion\python.exe", None, None),
+            (r"C:\Broken\NumericVersion\python.exe", None, None),
+        ]));
+        assert_eq!(
+            scan_fixtures(
+                "fixture_combined",
+                &["python-org", "conda", "pypy", "malformed"]
+            )?,
+            pythons
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn latest_managed_registry_python() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("latest_managed_registry_python");
+        assert!(registry
+            .run(super::latest_managed_registry_python)
+            .is_none());
+
+        registry.register(
+            crate::COMPANY_KEY,
+            "CPython3.12.8",
+            "3.12",
+            r"C:\Managed312\python.exe",
+        )?;
+        registry.register(
+            crate::COMPANY_KEY,
+            "CPython3.13.1",
+            "3.13",
+            r"C:\Managed313\python.exe",
+        )?;
+        registry.register("ExampleCorp", "3.14", "3.14", r"C:\Example\python.exe")?;
+        // Without a version.
+        CURRENT_USER
+            .create(format!(
+                r"{}\{}\Unversioned\InstallPath",
+                registry.python_key,
+                crate::COMPANY_KEY
+            ))?
+            .set_string("ExecutablePath", r"C:\Unversioned\python.exe")?;
+
+        let latest = registry.run(super::latest_managed_registry_python).unwrap();
+        assert_eq!(latest.path, PathBuf::from(r"C:\Managed313\python.exe"));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_utf16_path() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("invalid_utf16_path");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Placeholder\python.exe")?;
+        // `C:\Bad<unpaired surrogate>\python.exe`
+        let wide: Vec<u16> = r"C:\Bad"
+            .encode_utf16()
+            .chain([0xD800])
+            .chain(r"\python.exe".encode_utf16())
+            .chain([0])
+            .collect();
+        let bytes: Vec<u8> = wide.iter().flat_map(|unit| unit.to_le_bytes()).collect();
+        CURRENT_USER
+            .create(format!(
+                r"{}\ExampleCorp\3.12\InstallPath",
+                registry.python_key
+            ))?
+            .set_bytes("ExecutablePath", windows_registry::Type::String, &bytes)?;
+
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(pythons.len(), 1);
+        assert_eq!(
+            pythons[0].path,
+            PathBuf::from("C:\\Bad\u{FFFD}\\python.exe")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn windows_python_available() -> anyhow::Result<()> {
+        use crate::{PythonVariant, VersionRequest};
+
+        let registry = TestRegistry::new("windows_python_available");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example\python.exe")?;
+        assert!(registry.run(
+            || super::windows_python_available(&VersionRequest::MajorMinor(
+                3,
+                12,
+                PythonVariant::Default
+            ))
+        ));
+        assert!(!registry.run(
+            || super::windows_python_available(&VersionRequest::MajorMinor(
+                3,
+                99,
+                PythonVariant::Default
+            ))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn empty_executable_path() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("empty_executable_path");
+        registry.register("ExampleCorp", "3.12", "3.12", "")?;
+        registry.register("ExampleCorp", "3.13", "3.13", "  ")?;
+        assert!(registry.run(registry_pythons)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn unicode_round_trip() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("unicode_round_trip");
+        // Non-ASCII user name, with characters outside the basic multilingual plane.
+        let installation = ManagedPythonInstallation::from_path(PathBuf::from(
+            "C:\\Users\\Jürgen 😀 𝔘𝔳\\cpython-3.12.8-windows-x86_64-none",
+        ))?;
+        let display_name = "Exämple 🐍 Corp";
+        registry.register_installation(
+            &installation,
+            &super::RegistryMetadata::default().with_company_display_name(display_name.to_string()),
+        )?;
+
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(pythons.len(), 1);
+        assert_eq!(pythons[0].path, installation.executable(false));
+        assert_eq!(
+            pythons[0].windowed_path.as_ref(),
+            Some(&installation.executable(true))
+        );
+        let companies = registry.run(super::registry_companies);
+        let company = companies
+            .iter()
+            .find(|company| company.name == crate::COMPANY_KEY)
+            .unwrap();
+        assert_eq!(company.display_name.as_deref(), Some(display_name));
+        Ok(())
+    }
+
+    #[test]
+    fn major_only_tag() -> anyhow::Result<()> {
+        use crate::{PythonVariant, VersionRequest};
+
+        let registry = TestRegistry::new("major_only_tag");
+        CURRENT_USER
+            .create(format!(r"{}\PythonCore\3\InstallPath", registry.python_key))?
+            .set_string("ExecutablePath", r"C:\Python3\python.exe")?;
+        registry.register("PythonCore", "3.12", "3.12", r"C:\Python312\python.exe")?;
+
+        let pythons = registry.run(registry_pythons)?;
+        let versions: Vec<_> = pythons
+            .iter()
+            .map(|python| python.version.as_ref().unwrap().to_string())
+            .collect();
+        // The fully-specified version is preferred.
+        assert_eq!(versions, ["3.12", "3"]);
+        assert!(pythons[1].matches_version_request(
+            &VersionRequest::MajorMinor(3, 12, PythonVariant::Default),
+            false
+        ));
+        assert!(!pythons[1]
+            .matches_version_request(&VersionRequest::Major(2, PythonVariant::Default), false));
+        Ok(())
+    }
+
+    #[test]
+    fn same_interpreter() -> anyhow::Result<()> {
+        let python = |path: &Path| super::WindowsPython::new(path.to_path_buf(), None);
+
+        assert!(super::same_interpreter(
+            &python(Path::new(r"C:\Example\python.exe")),
+            &python(Path::new(r"c:/example/PYTHON.EXE"))
+        ));
+        assert!(!super::same_interpreter(
+            &python(Path::new(r"C:\Example\python.exe")),
+            &python(Path::new(r"C:\Other\python.exe"))
+        ));
+
+        // A path that doesn't normalize to the same string, but resolves to the same file.
+        let exe = std::env::current_exe()?;
+        let parent = exe.parent().unwrap();
+        let indirect = parent
+            .join("..")
+            .join(parent.file_name().unwrap())
+            .join(exe.file_name().unwrap());
+        assert!(super::same_interpreter(&python(&exe), &python(&indirect)));
+        Ok(())
+    }
+
+    #[test]
+    fn registry_description() -> anyhow::Result<()> {
+        let key = PythonInstallationKey::from_str("cpython-3.13.1-windows-x86_64-none")?;
+        assert_eq!(
+            super::registry_description(
+                &key,
+                Some(
+                    "https://github.com/astral-sh/python-build-standalone/releases/download/\
+                    20241206/cpython-3.13.1%2B20241206-x86_64-pc-windows-msvc-install_only.tar.gz"
+                )
+            ),
+            "Managed by uv: CPython 3.13.1 from python-build-standalone 20241206"
+        );
+        assert_eq!(
+            super::registry_description(&key, None),
+            "Managed by uv: CPython 3.13.1"
+        );
+
+        let registry = TestRegistry::new("registry_description");
+        let installation = cpython312()?;
+        let tag = || {
+            CURRENT_USER.open(format!(
+                r"{}\{}\CPython3.12.8",
+                registry.python_key,
+                crate::COMPANY_KEY
+            ))
+        };
+
+        registry.register_installation(
+            &installation,
+            &super::RegistryMetadata::default().with_description(true),
+        )?;
+        assert_eq!(
+            tag()?.get_string(super::DESCRIPTION_VALUE)?,
+            "Managed by uv: CPython 3.12.8"
+        );
+
+        // Registering again without a description removes it.
+        registry.register_installation(&installation, &super::RegistryMetadata::default())?;
+        assert!(tag()?.get_string(super::DESCRIPTION_VALUE).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn refresh_all_registry_entries() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("refresh_all_registry_entries");
+        // An entry of schema version 1, without the variant values.
+        registry.register(
+            crate::COMPANY_KEY,
+            "CPython3.12.8",
+            "3.12",
+            r"C:\uv-test\cpython-3.12.8-windows-x86_64-none\python.exe",
+        )?;
+        let company =
+            CURRENT_USER.create(format!(r"{}\{}", registry.python_key, crate::COMPANY_KEY))?;
+        company.set_u32(super::REGISTRY_SCHEMA_VALUE, 1)?;
+
+        let installations = [cpython312()?];
+        let mut errors = Vec::new();
+        registry.run(|| super::refresh_all_registry_entries(&installations, &mut errors))?;
+        assert!(errors.is_empty());
+
+        assert_eq!(
+            company.get_u32(super::REGISTRY_SCHEMA_VALUE)?,
+            super::REGISTRY_SCHEMA_VERSION
+        );
+        let tag = company.open("CPython3.12.8")?;
+        assert_eq!(tag.get_string(super::VARIANT_VALUE)?, "default");
+        assert_eq!(tag.get_string(super::BASE_VERSION_VALUE)?, "3.12.8");
+
+        // Refreshing again is a no-op.
+        let last_write_times = || -> anyhow::Result<(u64, u64)> {
+            Ok((
+                super::query_key_info(&tag)?.last_write_time,
+                super::query_key_info(&tag.open("InstallPath")?)?.last_write_time,
+            ))
+        };
+        let written = last_write_times()?;
+        registry.run(|| super::refresh_all_registry_entries(&installations, &mut errors))?;
+        assert!(errors.is_empty());
+        assert_eq!(tag.get_string("Version")?, "3.12.8");
+        assert_eq!(last_write_times()?, written);
+        Ok(())
+    }
+
+    #[test]
+    fn version_precedence() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("version_precedence");
+        let entries = [
+            ("Patch", "3.12", Some("3.12.4")),
+            ("OwnRelease", "3.11", Some("24.1")),
+            ("OtherMinor", "3.10", Some("3.9.1")),
+            ("SysVersionOnly", "3.9", None),
+            ("SamePrecision", "3.8.10", Some("3.8.12")),
+        ];
+        for (tag, sys_version, version) in entries {
+            registry.register(
+                "ExampleCorp",
+                tag,
+                sys_version,
+                &format!(r"C:\{tag}\python.exe"),
+            )?;
+            if let Some(version) = version {
+                CURRENT_USER
+                    .create(format!(r"{}\ExampleCorp\{tag}", registry.python_key))?
+                    .set_string("Version", version)?;
+            }
+        }
+
+        let pythons = registry.run(registry_pythons)?;
+        let versions: Vec<_> = pythons
+            .iter()
+            .map(|python| python.version.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(versions, ["3.12.4", "3.11", "3.10", "3.9", "3.8.10"]);
+        Ok(())
+    }
+
+    #[test]
+    fn registry_state_hash() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("registry_state_hash");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+
+        let hash = registry.run(super::registry_state_hash);
+        assert_eq!(registry.run(super::registry_state_hash), hash);
+
+        registry.register("ExampleCorp", "3.13", "3.13", r"C:\Example313\python.exe")?;
+        let added = registry.run(super::registry_state_hash);
+        assert_ne!(added, hash);
+
+        CURRENT_USER
+            .create(format!(
+                r"{}\ExampleCorp\3.13\InstallPath",
+                registry.python_key
+            ))?
+            .set_string("WindowedExecutablePath", r"C:\Example313\pythonw.exe")?;
+        assert_ne!(registry.run(super::registry_state_hash), added);
+
+        // The last write time has a coarse resolution, so fake it instead of waiting for a change.
+        let written_at = |last_write_time| {
+            move |key: &windows_registry::Key| {
+                super::query_key_info(key).map(|info| super::KeyInfo {
+                    last_write_time,
+                    ..info
+                })
+            }
+        };
+        let before = registry.run(|| super::registry_state_hash_with(&written_at(1)));
+        assert_eq!(
+            registry.run(|| super::registry_state_hash_with(&written_at(1))),
+            before
+        );
+        assert_ne!(
+            registry.run(|| super::registry_state_hash_with(&written_at(2))),
+            before
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn arch_mismatch_note() -> anyhow::Result<()> {
+        use crate::platform::Arch;
+        use target_lexicon::PointerWidth;
+
+        let x86_64 = Arch::from_str("x86_64")?;
+        let x86 = Arch::from_str("x86")?;
+        let aarch64 = Arch::from_str("aarch64")?;
+        assert_eq!(
+            super::arch_mismatch_note(Some(PointerWidth::U32), Some(x86), x86_64).as_deref(),
+            Some("32-bit, host is 64-bit")
+        );
+        // Without an architecture, the pointer width is enough.
+        assert_eq!(
+            super::arch_mismatch_note(Some(PointerWidth::U32), None, x86_64).as_deref(),
+            Some("32-bit, host is 64-bit")
+        );
+        assert_eq!(
+            super::arch_mismatch_note(Some(PointerWidth::U64), Some(x86_64), aarch64).as_deref(),
+            Some("x86_64, host is aarch64")
+        );
+        assert_eq!(
+            super::arch_mismatch_note(Some(PointerWidth::U64), Some(x86_64), x86_64),
+            None
+        );
+        assert_eq!(super::arch_mismatch_note(None, None, x86_64), None);
+        Ok(())
+    }
+
+    #[test]
+    fn version_or_infer() {
+        // The registered version is used without running the interpreter.
+        let python = super::WindowsPython::new(
+            PathBuf::from(r"C:\Missing\python.exe"),
+            Some(PythonVersion::from_str("3.12").unwrap()),
+        );
+        assert_eq!(python.version_or_infer().unwrap().to_string(), "3.12");
+
+        let python = super::WindowsPython::new(PathBuf::from(r"C:\Missing\python.exe"), None);
+        assert!(python.version_or_infer().is_err());
+    }
+
+    #[test]
+    fn registry_path_value() {
+        assert_eq!(
+            super::registry_path_value(Path::new("C:/uv-test/cpython-3.12.8/python.exe")),
+            r"C:\uv-test\cpython-3.12.8\python.exe"
+        );
+        assert_eq!(
+            super::registry_path_value(Path::new(r"C:\uv-test\python.exe")),
+            r"C:\uv-test\python.exe"
+        );
+    }
+
+    #[test]
+    fn py_launcher_default() {
+        assert_eq!(
+            super::parse_py_ini_default(
+                "[commands]\npython=3.11\n\n[Defaults]\n; comment\npython = 3.12-32\n"
+            )
+            .as_deref(),
+            Some("3.12-32")
+        );
+        assert_eq!(
+            super::parse_py_ini_default("[defaults]\npython3=3.12\n"),
+            None
+        );
+
+        let mut python = super::WindowsPython::new(
+            PathBuf::from(r"C:\Python312-32\python.exe"),
+            Some(PythonVersion::from_str("3.12.4").unwrap()),
+        );
+        python.pointer_width = Some(target_lexicon::PointerWidth::U32);
+        assert!(super::matches_py_launcher_tag(&python, "3"));
+        assert!(super::matches_py_launcher_tag(&python, "3.12"));
+        assert!(super::matches_py_launcher_tag(&python, "3.12-32"));
+        assert!(!super::matches_py_launcher_tag(&python, "3.12-64"));
+        assert!(!super::matches_py_launcher_tag(&python, "3.13"));
+        assert!(!super::matches_py_launcher_tag(&python, "3.1"));
+    }
+
+    #[test]
+    fn known_distribution() {
+        use super::KnownDistribution;
+
+        for (company, distribution) in [
+            ("PythonCore", Some(KnownDistribution::PythonOrg)),
+            ("ContinuumAnalytics", Some(KnownDistribution::Conda)),
+            ("ContinuumAnalytics64", Some(KnownDistribution::Conda)),
+            ("Anaconda3", Some(KnownDistribution::Conda)),
+            ("anaconda", Some(KnownDistribution::Conda)),
+            ("Miniconda", Some(KnownDistribution::Conda)),
+            ("PyPy", Some(KnownDistribution::PyPy)),
+            ("GraalPy", Some(KnownDistribution::GraalPy)),
+            ("Jython", Some(KnownDistribution::Jython)),
+            (crate::COMPANY_KEY, Some(KnownDistribution::Uv)),
+            ("ExampleCorp", None),
+            ("Py", None),
+        ] {
+            assert_eq!(
+                super::known_distribution(company),
+                distribution,
+                "{company}"
+            );
+        }
+        assert_eq!(
+            KnownDistribution::PyPy.implementation(),
+            Some(crate::ImplementationName::PyPy)
+        );
+        assert_eq!(
+            KnownDistribution::GraalPy.implementation(),
+            Some(crate::ImplementationName::GraalPy)
+        );
+        assert_eq!(KnownDistribution::Jython.implementation(), None);
+        assert!(!KnownDistribution::Jython.is_supported());
+    }
+
+    #[test]
+    fn unsupported_companies() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("unsupported_companies");
+        registry.register("GraalPy", "24.1", "3.11", r"C:\GraalPy\python.exe")?;
+        registry.register("Jython", "2.7", "2.7", r"C:\Jython\jython.exe")?;
+
+        let pythons = registry.run(|| {
+            registry_pythons_with(&RegistryScanOptions::default().with_include_python2(true))
+        })?;
+        assert_eq!(
+            pythons
+                .iter()
+                .map(|python| python.path.clone())
+                .collect::<Vec<_>>(),
+            [PathBuf::from(r"C:\GraalPy\python.exe")]
+        );
+        assert_eq!(
+            pythons[0].implementation(),
+            Some(crate::ImplementationName::GraalPy)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_executables() -> anyhow::Result<()> {
+        use uv_fs::Simplified;
+
+        let registry = TestRegistry::new("resolve_executables");
+        let exe = std::env::current_exe()?;
+        let parent = exe.parent().unwrap();
+        let indirect = parent
+            .join("..")
+            .join(parent.file_name().unwrap())
+            .join(exe.file_name().unwrap());
+        registry.register("ExampleCorp", "3.12", "3.12", exe.to_str().unwrap())?;
+        registry.register("OtherCorp", "3.12", "3.12", indirect.to_str().unwrap())?;
+
+        // By default, both registrations are returned.
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(pythons.len(), 2);
+        assert!(pythons.iter().all(|python| python.real_path.is_none()));
+
+        let options = RegistryScanOptions::default().with_resolve_executables(true);
+        let pythons = registry.run(|| registry_pythons_with(&options))?;
+        assert_eq!(pythons.len(), 1);
+        assert_eq!(pythons[0].path, exe);
+        assert_eq!(
+            pythons[0].real_path.as_deref(),
+            Some(exe.simple_canonicalize()?.as_path())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sort_by_recency() -> anyhow::Result<()> {
+        use std::time::{Duration, SystemTime};
+
+        let registry = TestRegistry::new("sort_by_recency");
+        registry.register("ExampleCorp", "3.13", "3.13", r"C:\Example313\python.exe")?;
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+
+        // The scan populates the last write time, and the recency order falls back to the version
+        // order for entries written at the same time.
+        let options =
+            RegistryScanOptions::default().with_sort_order(super::RegistrySortOrder::Recency);
+        let mut pythons = registry.run(|| registry_pythons_with(&options))?;
+        assert!(pythons
+            .iter()
+            .all(|python| python.last_write_time.is_some()));
+
+        // The last write time has a coarse resolution, so set it instead of waiting between
+        // registrations.
+        let written_at = |secs| Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+        let mut python311 = super::WindowsPython::new(
+            PathBuf::from(r"C:\Example311\python.exe"),
+            Some(PythonVersion::from_str("3.11").unwrap()),
+        );
+        python311.last_write_time = written_at(2);
+        pythons[0].last_write_time = written_at(1);
+        pythons[1].last_write_time = written_at(3);
+        pythons.push(python311);
+        pythons.push(super::WindowsPython::new(
+            PathBuf::from(r"C:\Example\python.exe"),
+            None,
+        ));
+        pythons.sort_by(super::cmp_by_recency);
+        assert_eq!(
+            pythons
+                .iter()
+                .map(|python| python.version.as_ref().map(ToString::to_string))
+                .collect::<Vec<_>>(),
+            [
+                Some("3.12".to_string()),
+                Some("3.11".to_string()),
+                Some("3.13".to_string()),
+                None
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn is_reserved_company() {
+        assert!(super::is_reserved_company("PyLauncher"));
+        assert!(super::is_reserved_company("pylauncher"));
+        assert!(!super::is_reserved_company("PythonCore"));
+        assert!(!super::is_reserved_company("PyLauncher2"));
+    }
+
+    #[test]
+    fn default_preference() -> anyhow::Result<()> {
+        use super::DefaultPreference;
+
+        let registry = TestRegistry::new("default_preference");
+        let company =
+            CURRENT_USER.create(format!(r"{}\{}", registry.python_key, crate::COMPANY_KEY))?;
+        assert_eq!(super::read_default_preference(&company), None);
+
+        company.set_string(super::DEFAULT_VERSION_VALUE, "3.12-32")?;
+        assert_eq!(
+            super::read_default_preference(&company),
+            Some(DefaultPreference::Version("3.12-32".to_string()))
+        );
+        assert_eq!(
+            super::read_default_preference(&company)
+                .unwrap()
+                .designation(),
+            super::DefaultDesignation::Version
+        );
+
+        // The tag takes precedence.
+        company.set_string(super::DEFAULT_TAG_VALUE, r"PythonCore\3.12")?;
+        let preference = super::read_default_preference(&company).unwrap();
+        let python = super::WindowsPython::new(
+            PathBuf::from(r"C:\Python312\python.exe"),
+            Some(PythonVersion::from_str("3.12").unwrap()),
+        );
+        assert_eq!(preference.designation(), super::DefaultDesignation::Tag);
+        assert!(preference.matches("pythoncore", "3.12", &python));
+        assert!(!preference.matches("PythonCore", "3.12-32", &python));
+        Ok(())
+    }
+
+    #[test]
+    fn display_windows_python() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("display_windows_python");
+        registry.register("PythonCore", "3.12", "3.12", r"C:\Python312\python.exe")?;
+        CURRENT_USER
+            .create(format!(r"{}\PythonCore\3.12", registry.python_key))?
+            .set_string("SysArchitecture", "64bit")?;
+
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(
+            pythons[0].to_string(),
+            r"CPython 3.12 (64-bit) - C:\Python312\python.exe [HKCU]"
+        );
+
+        // Unknown parts are omitted.
+        let python = super::WindowsPython::new(PathBuf::from(r"C:\Example\python.exe"), None);
+        assert_eq!(python.to_string(), r"Python - C:\Example\python.exe");
+        Ok(())
+    }
+
+    #[test]
+    fn additional_base_paths() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("additional_base_paths");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+        let vendor_key = format!(
+            r"Software\uv-test\{}\additional_base_paths\Vendor",
+            std::process::id()
+        );
+        let _ = CURRENT_USER.remove_tree(&vendor_key);
+        let tag = CURRENT_USER.create(format!(r"{vendor_key}\VendorCorp\3.13"))?;
+        tag.set_string("SysVersion", "3.13")?;
+        tag.create("InstallPath")?
+            .set_string("ExecutablePath", r"C:\Vendor313\python.exe")?;
+
+        let paths = |options: &RegistryScanOptions| -> anyhow::Result<Vec<PathBuf>> {
+            Ok(registry
+                .run(|| registry_pythons_with(options))?
+                .into_iter()
+                .map(|python| python.path)
+                .collect())
+        };
+        let options = RegistryScanOptions::default().with_roots(vec![RegistryRoot::CurrentUser]);
+        assert_eq!(
+            paths(&options)?,
+            [PathBuf::from(r"C:\Example312\python.exe")]
+        );
+        let options = options.with_additional_base_paths(vec![vendor_key.clone()]);
+        assert_eq!(
+            paths(&options)?,
+            [
+                PathBuf::from(r"C:\Vendor313\python.exe"),
+                PathBuf::from(r"C:\Example312\python.exe")
+            ]
+        );
+
+        CURRENT_USER.remove_tree(&vendor_key)?;
+        Ok(())
+    }
+
+    #[test]
+    fn company_key_creation_error() {
+        let err = super::RegistryWriteError::CreateCompanyKey {
+            path: r"HKCU\Software\Python\Astral".to_string(),
+            err: windows_result::Error::from_hresult(windows_result::HRESULT::from_win32(
+                windows_sys::Win32::Foundation::ERROR_ACCESS_DENIED,
+            )),
+        };
+        assert_eq!(
+            err.to_string(),
+            r"Failed to create the registry key `HKCU\Software\Python\Astral`"
+        );
+        assert!(!super::is_transient_registry_error(err.registry_error()));
+    }
+
+    #[test]
+    fn install_path_prefix_round_trip() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("install_path_prefix_round_trip");
+        let installation = cpython312()?;
+        let mut errors = Vec::new();
+        registry.run(|| {
+            super::refresh_all_registry_entries(std::slice::from_ref(&installation), &mut errors)
+        })?;
+        assert!(errors.is_empty());
+
+        // The writer sets the default value with an empty name, which the reader reads back.
+        let install_path = CURRENT_USER.open(format!(
+            r"{}\{}\CPython3.12.8\InstallPath",
+            registry.python_key,
+            crate::COMPANY_KEY
+        ))?;
+        assert_eq!(
+            install_path.get_string("")?,
+            r"C:\uv-test\cpython-3.12.8-windows-x86_64-none"
+        );
+        let pythons = registry.run(|| {
+            registry_pythons_with(
+                &RegistryScanOptions::default().with_roots(vec![RegistryRoot::CurrentUser]),
+            )
+        })?;
+        assert_eq!(pythons.len(), 1);
+        assert_eq!(pythons[0].prefix.as_deref(), Some(installation.path()));
+
+        // Entries without a default value have no prefix.
+        registry.register("ExampleCorp", "3.13", "3.13", r"C:\Example313\python.exe")?;
+        let pythons = registry.run(|| {
+            registry_pythons_with(
+                &RegistryScanOptions::default().with_roots(vec![RegistryRoot::CurrentUser]),
+            )
+        })?;
+        let example = pythons
+            .iter()
+            .find(|python| python.company.as_deref() == Some("ExampleCorp"))
+            .unwrap();
+        assert_eq!(example.prefix, None);
+        Ok(())
+    }
+
+    #[test]
+    fn diff_registry_vs_path() {
+        let registry_pythons = vec![
+            super::WindowsPython::new(PathBuf::from(r"C:\Python312\python.exe"), None),
+            super::WindowsPython::new(PathBuf::from(r"C:\Python313\python.exe"), None),
+        ];
+        let path_pythons = [
+            PathBuf::from(r"c:\python313\python.exe"),
+            PathBuf::from(r"C:\Tools\python.exe"),
+        ];
+        let diff = super::diff_pythons(registry_pythons, &path_pythons);
+        assert_eq!(
+            diff.registry_only
+                .iter()
+                .map(|python| python.path.clone())
+                .collect::<Vec<_>>(),
+            [PathBuf::from(r"C:\Python312\python.exe")]
+        );
+        assert_eq!(diff.path_only, [PathBuf::from(r"C:\Tools\python.exe")]);
+        assert_eq!(diff.both.len(), 1);
+        assert_eq!(
+            diff.both[0].0.path,
+            PathBuf::from(r"C:\Python313\python.exe")
+        );
+        assert_eq!(diff.both[0].1, PathBuf::from(r"c:\python313\python.exe"));
+    }
+
+    #[test]
+    fn summarize_skipped() {
+        let mut skipped = super::SkippedEntries {
+            summarize: true,
+            ..super::SkippedEntries::default()
+        };
+        skipped.skip(
+            "without `ExecutablePath`",
+            &r"Software\Python\ExampleCorp\3.12",
+        );
+        skipped.skip(
+            "without `ExecutablePath`",
+            &r"Software\Python\ExampleCorp\3.13",
+        );
+        skipped.skip("Python 2", &r"C:\Python27\python.exe");
+        assert_eq!(
+            skipped.counts.into_iter().collect::<Vec<_>>(),
+            [("Python 2", 1), ("without `ExecutablePath`", 2)]
+        );
+
+        // By default, each entry is logged instead.
+        let mut skipped = super::SkippedEntries::default();
+        skipped.skip("Python 2", &r"C:\Python27\python.exe");
+        assert!(skipped.counts.is_empty());
+    }
+
+    #[test]
+    fn register_latest_alias() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("register_latest_alias");
+        let cpython312 = cpython312()?;
+        let cpython313 = test_installation("cpython-3.13.1-windows-x86_64-none")?;
+        let cpython313t = test_installation("cpython-3.13.1+freethreaded-windows-x86_64-none")?;
+        let alias_executable = || -> anyhow::Result<String> {
+            let install_path = CURRENT_USER.open(format!(
+                r"{}\{}\{}\InstallPath",
+                registry.python_key,
+                crate::COMPANY_KEY,
+                super::LATEST_TAG
+            ))?;
+            Ok(install_path.get_string("ExecutablePath")?)
+        };
+
+        let mut errors = Vec::new();
+        let installations = [cpython312.clone(), cpython313t, cpython313.clone()];
+        registry.run(|| super::register_latest_alias(&installations, &mut errors))?;
+        assert!(errors.is_empty());
+        assert_eq!(
+            alias_executable()?,
+            cpython313.executable(false).to_string_lossy()
+        );
+        // The alias isn't returned as a separate Python.
+        assert!(registry.run(registry_pythons)?.is_empty());
+
+        // After removing the highest version, the alias points to the next one.
+        registry.run(|| super::register_latest_alias(&[cpython312.clone()], &mut errors))?;
+        assert!(errors.is_empty());
+        assert_eq!(
+            alias_executable()?,
+            cpython312.executable(false).to_string_lossy()
+        );
+
+        registry.run(|| super::register_latest_alias(&[], &mut errors))?;
+        assert!(alias_executable().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn comma_version() -> anyhow::Result<()> {
+        assert_eq!(
+            super::normalize_comma_version("3,12").as_deref(),
+            Some("3.12")
+        );
+        assert_eq!(
+            super::normalize_comma_version("3,12,4").as_deref(),
+            Some("3.12.4")
+        );
+        assert_eq!(super::normalize_comma_version("3.12"), None);
+        assert_eq!(super::normalize_comma_version("3,12rc1"), None);
+        assert_eq!(super::normalize_comma_version("3,,12"), None);
+        assert_eq!(super::normalize_comma_version("Python 3,12"), None);
+
+        let registry = TestRegistry::new("comma_version");
+        registry.register("ExampleCorp", "3.12", "3,12", r"C:\Example312\python.exe")?;
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(
+            pythons[0].version,
+            Some(PythonVersion::from_str("3.12").unwrap())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scan_cancellation() -> anyhow::Result<()> {
+        use std::sync::atomic::AtomicBool;
+        use std::sync::Arc;
+
+        let registry = TestRegistry::new("scan_cancellation");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let options = RegistryScanOptions::default()
+            .with_roots(vec![RegistryRoot::CurrentUser])
+            .with_cancellation(cancelled.clone());
+        assert_eq!(registry.run(|| registry_pythons_with(&options))?.len(), 1);
+
+        cancelled.store(true, std::sync::atomic::Ordering::Relaxed);
+        assert!(registry.run(|| registry_pythons_with(&options))?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn registry_pythons_async() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("registry_pythons_async");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+
+        // The sandbox key is thread-local, so the future must be polled on this thread.
+        let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+        let options = RegistryScanOptions::default().with_roots(vec![RegistryRoot::CurrentUser]);
+        let pythons = registry.run(|| runtime.block_on(super::registry_pythons_async(options)))?;
+        assert_eq!(pythons.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn venv_base_executable() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let venv = temp_dir.path().join("venv");
+        fs_err::create_dir_all(venv.join("Scripts"))?;
+        fs_err::write(
+            venv.join("pyvenv.cfg"),
+            "home = C:\\Python312\nversion_info = 3.12.8\n",
+        )?;
+
+        let python = super::WindowsPython::new(venv.join("Scripts").join("python.exe"), None);
+        assert_eq!(
+            python.venv_base_executable(),
+            Some(PathBuf::from(r"C:\Python312\python.exe"))
+        );
+
+        // A base interpreter doesn't have a `pyvenv.cfg`.
+        let python = super::WindowsPython::new(temp_dir.path().join("python.exe"), None);
+        assert_eq!(python.venv_base_executable(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn group_by_version() {
+        let python = |path: &str, version: Option<&str>, arch: Option<&str>| {
+            let mut python = super::WindowsPython::new(
+                PathBuf::from(path),
+                version.map(|version| PythonVersion::from_str(version).unwrap()),
+            );
+            python.arch = arch.map(|arch| crate::platform::Arch::from_str(arch).unwrap());
+            python
+        };
+        let pythons = [
+            python(r"C:\Python313\python.exe", Some("3.13.1"), Some("x86_64")),
+            python(r"C:\Python312\python.exe", Some("3.12.8"), Some("x86_64")),
+            python(r"C:\Python312-32\python.exe", Some("3.12.8"), Some("x86")),
+            python(r"C:\Example\python.exe", None, None),
+        ];
+        let groups = super::group_by_version(&pythons);
+        assert_eq!(
+            groups
+                .iter()
+                .map(|group| (
+                    group.version.as_ref().map(ToString::to_string),
+                    group.pythons.len()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (Some("3.13.1".to_string()), 1),
+                (Some("3.12.8".to_string()), 2),
+                (None, 1)
+            ]
+        );
+        assert_eq!(
+            groups[1].pythons[1],
+            (
+                Some(crate::platform::Arch::from_str("x86").unwrap()),
+                PathBuf::from(r"C:\Python312-32\python.exe")
+            )
+        );
+    }
+
+    #[test]
+    fn store_shim_shadowing_message() {
+        let shim =
+            PathBuf::from(r"C:\Users\Example\AppData\Local\Microsoft\WindowsApps\python.exe");
+        assert_eq!(super::store_shim_shadowing_message(&shim, &[]), None);
+
+        let pythons = [super::WindowsPython::new(
+            PathBuf::from(r"C:\Python312\python.exe"),
+            Some(PythonVersion::from_str("3.12.8").unwrap()),
+        )];
+        assert_eq!(
+            super::store_shim_shadowing_message(&shim, &pythons).as_deref(),
+            Some(
+                r#"`python` on `PATH` is the Microsoft Store alias `C:\Users\Example\AppData\Local\Microsoft\WindowsApps\python.exe`, but Python 3.12.8 is registered at `C:\Python312\python.exe`. Add `C:\Python312` to `PATH` before the alias, or disable the alias in Settings under "App execution aliases""#
+            )
+        );
+    }
+
+    #[test]
+    fn export_import_managed_registry() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("export_import_managed_registry");
+        let installations = [cpython312()?];
+        let mut errors = Vec::new();
+        registry.run(|| super::refresh_all_registry_entries(&installations, &mut errors))?;
+        assert!(errors.is_empty());
+        let company = format!(r"{}\{}", registry.python_key, crate::COMPANY_KEY);
+        CURRENT_USER.create(&company)?.set_bytes(
+            "Binary",
+            windows_registry::Type::Bytes,
+            &[0, 255],
+        )?;
+
+        let snapshot = registry.run(super::export_managed_registry)?;
+        let snapshot = super::SerializedRegistry::from_json(&snapshot.to_json())?;
+        CURRENT_USER.remove_tree(&company)?;
+        assert!(registry.run(super::managed_registry_pythons)?.is_empty());
+
+        registry.run(|| super::import_managed_registry(&snapshot))?;
+        assert_eq!(registry.run(super::export_managed_registry)?, snapshot);
+        assert_eq!(registry.run(super::managed_registry_pythons)?.len(), 1);
+
+        // Snapshots can't write outside of our company key.
+        let escaping = super::SerializedRegistry::from_json(
+            r#"{ "values": {}, "keys": { "..\\Other": { "values": {}, "keys": {} } } }"#,
+        )?;
+        assert!(matches!(
+            registry.run(|| super::import_managed_registry(&escaping)),
+            Err(super::RegistryImportError::Invalid(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn install_source() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("install_source");
+        let installations = [cpython312()?];
+        let mut errors = Vec::new();
+        registry.run(|| super::refresh_all_registry_entries(&installations, &mut errors))?;
+        assert!(errors.is_empty());
+        registry.register("PythonCore", "3.13", "3.13", r"C:\Python313\python.exe")?;
+        CURRENT_USER
+            .create(format!(r"{}\PythonCore", registry.python_key))?
+            .set_string("DisplayName", "Python Software Foundation")?;
+        registry.register("ExampleCorp", "3.11", "3.11", r"C:\Example311\python.exe")?;
+
+        let pythons = registry.run(registry_pythons)?;
+        let install_source = |path: &Path| {
+            pythons
+                .iter()
+                .find(|python| python.path == path)
+                .unwrap()
+                .install_source
+                .clone()
+        };
+        assert_eq!(
+            install_source(&installations[0].executable(false)).as_deref(),
+            Some("uv")
+        );
+        // Other entries fall back to the `DisplayName` of the company.
+        assert_eq!(
+            install_source(Path::new(r"C:\Python313\python.exe")).as_deref(),
+            Some("Python Software Foundation")
+        );
+        assert_eq!(install_source(Path::new(r"C:\Example311\python.exe")), None);
+        Ok(())
+    }
+
+    #[test]
+    fn local_machine_read_only() -> anyhow::Result<()> {
+        let installation = cpython312()?;
+        let mut errors = Vec::new();
+        let result = super::create_registry_entry_in(
+            &RegistryRoot::LocalMachine,
+            &installation,
+            &super::RegistryMetadata::default(),
+            &mut errors,
+        );
+        assert!(matches!(
+            result,
+            Err(super::ManagedPep514Error::LocalMachineNotAllowed(_))
+        ));
+        assert!(errors.is_empty());
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn sys_version_round_trip(
+            minor in 0u8..=30,
+            patch in 0u8..=50,
+            prerelease in proptest::option::of((
+                proptest::sample::select(vec!["a", "b", "rc"]),
+                0u8..=20,
+            )),
+        ) {
+            let prerelease = prerelease
+                .map(|(kind, number)| format!("{kind}{number}"))
+                .unwrap_or_default();
+            let key = PythonInstallationKey::from_str(&format!(
+                "cpython-3.{minor}.{patch}{prerelease}-windows-x86_64-none"
+            ))
+            .unwrap();
+            let sys_version = PythonVersion::from_str(&key.sys_version()).unwrap();
+            let version = key.version();
+            prop_assert_eq!(
+                (
+                    sys_version.major(),
+                    sys_version.minor(),
+                    sys_version.patch()
+                ),
+                (version.major(), version.minor(), version.patch()),
+                "{}",
+                key
+            );
+            prop_assert_eq!(sys_version.pre(), None, "{}", key);
+        }
+    }
+
+    #[test]
+    fn alternate_executable_values() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("alternate_executable_values");
+        let tag = CURRENT_USER.create(format!(r"{}\ExampleCorp\3.12", registry.python_key))?;
+        tag.set_string("SysVersion", "3.12")?;
+        let install_path = tag.create("InstallPath")?;
+        install_path.set_string("python.exe", r"C:\Alternate\python.exe")?;
+
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(pythons[0].path, PathBuf::from(r"C:\Alternate\python.exe"));
+
+        // A conforming `ExecutablePath` takes precedence.
+        install_path.set_string("ExecutablePath", r"C:\Example\python.exe")?;
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(pythons[0].path, PathBuf::from(r"C:\Example\python.exe"));
+        Ok(())
+    }
+
+    #[test]
+    fn registry_python_count() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("registry_python_count");
+        assert_eq!(registry.run(super::registry_python_count), 0);
+
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+        registry.register("ExampleCorp", "3.13", "3.13", r"C:\Example313\python.exe")?;
+        // Entries without an executable aren't counted.
+        CURRENT_USER
+            .create(format!(r"{}\ExampleCorp\3.11", registry.python_key))?
+            .set_string("SysVersion", "3.11")?;
+        assert_eq!(registry.run(super::registry_python_count), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn write_install_path() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("write_install_path");
+        let installations = [cpython312()?];
+        let tag = CURRENT_USER.create(format!(
+            r"{}\{}\CPython3.12.8",
+            registry.python_key,
+            crate::COMPANY_KEY
+        ))?;
+        // The leftovers of interrupted writes, of a process that isn't running anymore and of an
+        // earlier version of uv, and an `InstallPath` with a value of another tool.
+        let leftovers = [
+            format!("{}-{}-0", super::INSTALL_PATH_TEMP_PREFIX, u32::MAX - 2),
+            super::INSTALL_PATH_TEMP_PREFIX.to_string(),
+        ];
+        for leftover in &leftovers {
+            tag.create(leftover)?
+                .set_string("ExecutablePath", r"C:\Stale\python.exe")?;
+        }
+        tag.create("InstallPath")?.set_string("Custom", "value")?;
+
+        let mut errors = Vec::new();
+        registry.run(|| super::refresh_all_registry_entries(&installations, &mut errors))?;
+        assert!(errors.is_empty());
+
+        assert_eq!(tag.keys()?.collect::<Vec<_>>(), ["InstallPath"]);
+        let install_path = tag.open("InstallPath")?;
+        assert_eq!(
+            install_path.get_string("")?,
+            r"C:\uv-test\cpython-3.12.8-windows-x86_64-none"
+        );
+        assert_eq!(
+            install_path.get_string("ExecutablePath")?,
+            installations[0].executable(false).to_string_lossy()
+        );
+        assert_eq!(
+            install_path.get_string("WindowedExecutablePath")?,
+            installations[0].executable(true).to_string_lossy()
+        );
+        assert_eq!(install_path.get_string("Custom")?, "value");
+        Ok(())
+    }
+
+    #[test]
+    fn watched_views() {
+        use super::RegistryView;
+
+        // `Software` exists in both views of `HKEY_LOCAL_MACHINE`, on 32-bit Windows, there is only
+        // one view.
+        let views = super::watched_views("Software");
+        let mut expected = vec![
+            (RegistryRoot::CurrentUser, RegistryView::Shared),
+            (RegistryRoot::LocalMachine, RegistryView::Native),
+        ];
+        if cfg!(target_pointer_width = "64") || super::is_wow64_process() {
+            expected.push((RegistryRoot::LocalMachine, RegistryView::Wow64));
+        }
+        assert_eq!(views, expected);
+        for (root, view) in views {
+            assert!(root.open_view(view, "Software").is_ok());
+        }
+    }
+
+    #[test]
+    fn interleaved_install_path_writers() -> anyhow::Result<()> {
+        use windows_registry::Value;
+
+        let registry = TestRegistry::new("interleaved_install_path_writers");
+        let tag = CURRENT_USER.create(format!(r"{}\ExampleCorp\3.12", registry.python_key))?;
+        let values = |executable: &str| [("ExecutablePath", Value::from(executable))];
+        let executable = || -> anyhow::Result<String> {
+            Ok(tag.open("InstallPath")?.get_string("ExecutablePath")?)
+        };
+
+        // Both writers stage their values before either replaces the key, so neither removes the
+        // key of the other.
+        let first = super::stage_install_path(&tag, &values(r"C:\First\python.exe"))?.unwrap();
+        let second = super::stage_install_path(&tag, &values(r"C:\Second\python.exe"))?.unwrap();
+        assert_ne!(first, second);
+        super::replace_install_path(&tag, &second)?;
+        assert_eq!(executable()?, r"C:\Second\python.exe");
+        super::replace_install_path(&tag, &first)?;
+        assert_eq!(executable()?, r"C:\First\python.exe");
+        assert_eq!(tag.keys()?.collect::<Vec<_>>(), ["InstallPath"]);
+
+        // A staged key of a running writer isn't mistaken for a leftover.
+        let staged = super::stage_install_path(&tag, &values(r"C:\Third\python.exe"))?.unwrap();
+        super::write_install_path(&tag, &values(r"C:\Fourth\python.exe"))?;
+        assert!(tag.open(&staged).is_ok());
+        super::replace_install_path(&tag, &staged)?;
+        assert_eq!(executable()?, r"C:\Third\python.exe");
+
+        assert!(super::is_transient_registry_error(
+            &windows_result::Error::from_hresult(windows_result::HRESULT::from_win32(
+                windows_sys::Win32::Foundation::ERROR_ALREADY_EXISTS
+            ))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn registry_view_access() {
+        use windows_sys::Win32::System::Registry::{KEY_WOW64_32KEY, KEY_WOW64_64KEY};
+
+        assert_eq!(super::registry_view_access(64), KEY_WOW64_64KEY);
+        assert_eq!(super::registry_view_access(32), KEY_WOW64_32KEY);
+    }
+
+    #[test]
+    fn registry_pythons_by_company() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("registry_pythons_by_company");
+        registry.register("PythonCore", "3.13", "3.13", r"C:\Python313\python.exe")?;
+        registry.register("PythonCore", "3.12", "3.12", r"C:\Python312\python.exe")?;
+        registry.register("ExampleCorp", "3.11", "3.11", r"C:\Example311\python.exe")?;
+        CURRENT_USER
+            .create(format!(r"{}\PythonCore", registry.python_key))?
+            .set_string("DisplayName", "Python Software Foundation")?;
+        // A company without valid entries.
+        CURRENT_USER.create(format!(r"{}\EmptyCorp\3.10", registry.python_key))?;
+
+        let groups = registry.run(super::registry_pythons_by_company)?;
+        assert_eq!(
+            groups
+                .iter()
+                .map(|group| (
+                    group.company.as_str(),
+                    group.display_name.as_deref(),
+                    group
+                        .pythons
+                        .iter()
+                        .map(|python| python.tag.clone().unwrap())
+                        .collect::<Vec<_>>()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    "PythonCore",
+                    Some("Python Software Foundation"),
+                    vec!["3.13".to_string(), "3.12".to_string()]
+                ),
+                ("ExampleCorp", None, vec!["3.11".to_string()]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn display_version() {
+        let version = |version: &str| Some(PythonVersion::from_str(version).unwrap());
+        let mut python =
+            super::WindowsPython::new(PathBuf::from(r"C:\Python312\python.exe"), version("3.12"));
+        python.full_version = version("3.12.4");
+        assert_eq!(python.display_version(), version("3.12.4").as_ref());
+
+        // A distribution release number isn't shown as the Python version.
+        python.full_version = version("7.3");
+        assert_eq!(python.display_version(), version("3.12").as_ref());
+    }
+
+    #[test]
+    fn duplicate_display_names() {
+        let python = |company: &str, display_name: &str| {
+            let mut python =
+                super::WindowsPython::new(PathBuf::from(format!(r"C:\{company}\python.exe")), None);
+            python.company = Some(company.to_string());
+            python.display_name = Some(display_name.to_string());
+            python
+        };
+        let pythons = [
+            python("PythonCore", "Python 3.12 (64-bit)"),
+            python("ContinuumAnalytics", "Python 3.12 (64-bit)"),
+            python("PythonCore", "Python 3.11 (64-bit)"),
+            python("PythonCore", "Python 3.11 (64-bit)"),
+        ];
+
+        let duplicates = super::duplicate_display_names(&pythons);
+        assert_eq!(
+            duplicates,
+            std::collections::HashSet::from(["Python 3.12 (64-bit)".to_string()])
+        );
+        assert_eq!(
+            pythons[1].unambiguous_display_name(&duplicates).as_deref(),
+            Some("Python 3.12 (64-bit) (ContinuumAnalytics)")
+        );
+        assert_eq!(
+            pythons[2].unambiguous_display_name(&duplicates).as_deref(),
+            Some("Python 3.11 (64-bit)")
+        );
+    }
+
+    #[test]
+    fn update_registry_metadata() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("update_registry_metadata");
+        let installation = cpython312()?;
+        let err = registry
+            .run(|| super::update_registry_metadata(&installation))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            super::ManagedPep514Error::MissingRegistryEntry(_)
+        ));
+
+        let executable = r"C:\Custom\python.exe";
+        registry.register(crate::COMPANY_KEY, "CPython3.12.8", "3.12", executable)?;
+        let tag = CURRENT_USER.create(format!(
+            r"{}\{}\CPython3.12.8",
+            registry.python_key,
+            crate::COMPANY_KEY
+        ))?;
+        tag.set_string("DownloadSha256", "0000")?;
+
+        registry.run(|| super::update_registry_metadata(&installation))?;
+        // The installation has no hash, so the hash of the previous build is removed.
+        assert!(tag.get_string("DownloadSha256").is_err());
+        assert_eq!(
+            tag.get_string(super::INSTALL_SOURCE_VALUE)?,
+            super::INSTALL_SOURCE_UV
+        );
+        assert_eq!(
+            tag.open("InstallPath")?.get_string("ExecutablePath")?,
+            executable
+        );
+        Ok(())
+    }
+
+    /// Feed generated malformed values into the parsing of registry entries, checking that each
+    /// entry is either read or skipped without panicking.
+    /// Fragments of real registry values, combined into malformed ones.
+    const FRAGMENTS: &[&str] = &[
+        "",
+        "3",
+        "12",
+        ".",
+        ",",
+        " ",
+        "rc",
+        "a1",
+        "t",
+        "-",
+        "+",
+        "*",
+        "\\",
+        "/",
+        ":",
+        "\"",
+        "%PATH%",
+        "C:",
+        "python.exe",
+        "..",
+        "999999999999999999999",
+        "256",
+        "é",
+        "🐍",
+        "\t",
+    ];
+
+    /// A value built from [`FRAGMENTS`].
+    fn fragmented_string() -> impl Strategy<Value = String> {
+        proptest::collection::vec(proptest::sample::select(FRAGMENTS), 0..6)
+            .prop_map(|fragments| fragments.concat())
+    }
+
+    /// A registry value of any type, with a payload that may or may not match the type.
+    fn registry_value() -> impl Strategy<Value = (windows_registry::Type, Vec<u8>)> {
+        use windows_registry::Type;
+
+        fn wide(value: &str) -> impl Iterator<Item = u8> + '_ {
+            value.encode_utf16().chain([0]).flat_map(u16::to_le_bytes)
+        }
+
+        let ty = prop_oneof![
+            Just(Type::String),
+            Just(Type::ExpandString),
+            Just(Type::MultiString),
+            Just(Type::U32),
+            Just(Type::U64),
+            Just(Type::Bytes),
+            // `REG_NONE`, `REG_DWORD_BIG_ENDIAN`, `REG_LINK` and `REG_RESOURCE_LIST`.
+            proptest::sample::select(vec![0, 5, 6, 8]).prop_map(Type::Other),
+        ];
+        let payload = prop_oneof![
+            fragmented_string().prop_map(|value| wide(&value).collect()),
+            proptest::collection::vec(fragmented_string(), 0..4).prop_map(|lines| {
+                lines
+                    .iter()
+                    .flat_map(|line| wide(line))
+                    .chain([0, 0])
+                    .collect()
+            }),
+            proptest::collection::vec(any::<u8>(), 0..32),
+        ];
+        (ty, payload)
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn malformed_registry_values(
+            tags in proptest::collection::vec(proptest::array::uniform5(registry_value()), 1..20),
+        ) {
+            let registry = TestRegistry::new("malformed_registry_values");
+            for (tag, values) in tags.iter().enumerate() {
+                let tag_key =
+                    CURRENT_USER.create(format!(r"{}\ExampleCorp\{tag}", registry.python_key))?;
+                let install_path = tag_key.create("InstallPath")?;
+                let [sys_version, version, sys_architecture, display_name, executable] = values;
+                tag_key.set_bytes("SysVersion", sys_version.0, &sys_version.1)?;
+                tag_key.set_bytes("Version", version.0, &version.1)?;
+                tag_key.set_bytes("SysArchitecture", sys_architecture.0, &sys_architecture.1)?;
+                tag_key.set_bytes("DisplayName", display_name.0, &display_name.1)?;
+                install_path.set_bytes("ExecutablePath", executable.0, &executable.1)?;
+            }
+
+            let pythons = registry.run(registry_pythons)?;
+            prop_assert!(pythons.len() <= tags.len());
+            for python in &pythons {
+                prop_assert!(!python.path.as_os_str().is_empty(), "{:?}", python);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn normalize_comma_version_removes_commas(value in fragmented_string()) {
+            if let Some(normalized) = super::normalize_comma_version(&value) {
+                prop_assert!(!normalized.contains(','), "{:?}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn relative_executable_paths() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("relative_executable_paths");
+        registry.register("ExampleCorp", "3.12", "3.12", "python.exe")?;
+        let install_path = CURRENT_USER.create(format!(
+            r"{}\ExampleCorp\3.12\InstallPath",
+            registry.python_key
+        ))?;
+        install_path.set_string("", r"C:\Example")?;
+        install_path.set_string("WindowedExecutablePath", "pythonw.exe")?;
+
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(pythons[0].path, PathBuf::from(r"C:\Example\python.exe"));
+        assert_eq!(
+            pythons[0].windowed_path,
+            Some(PathBuf::from(r"C:\Example\pythonw.exe"))
+        );
+
+        assert_eq!(
+            super::resolve_relative_to_prefix(
+                r"D:\Other\python.exe",
+                Some(Path::new(r"C:\Example"))
+            ),
+            None
+        );
+        assert_eq!(super::resolve_relative_to_prefix("python.exe", None), None);
+        Ok(())
+    }
+
+    #[test]
+    fn windows_python_sys_version() {
+        let key = PythonInstallationKey::from_str("cpython-3.13.1rc1-windows-x86_64-none").unwrap();
+        let mut python = super::WindowsPython::new(
+            PathBuf::from(r"C:\Python313\python.exe"),
+            Some(PythonVersion::from_str("3.13").unwrap()),
+        );
+        python.arch = Some(*key.arch());
+        // Without a patch version, the entry can't be compared to a key.
+        assert_eq!(python.sys_version(), None);
+
+        python.full_version = Some(key.version());
+        assert_eq!(python.sys_version(), Some(key.sys_version()));
+
+        python.arch = None;
+        assert_eq!(python.sys_version(), None);
+    }
+
+    #[test]
+    fn arch_from_pe_header() {
+        let mut header = vec![0; 0x90];
+        header[..2].copy_from_slice(b"MZ");
+        header[0x3c..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        header[0x80..0x84].copy_from_slice(b"PE\0\0");
+        header[0x84..0x86].copy_from_slice(&super::IMAGE_FILE_MACHINE_ARM64.to_le_bytes());
+        assert_eq!(
+            super::arch_from_pe_header(&header).map(|arch| arch.family()),
+            Some(target_lexicon::Architecture::Aarch64(
+                target_lexicon::Aarch64Architecture::Aarch64
+            ))
+        );
+
+        // Truncated and non-PE files have no architecture.
+        assert!(super::arch_from_pe_header(&header[..0x82]).is_none());
+        assert!(super::arch_from_pe_header(b"#!/bin/sh").is_none());
+    }
+
+    #[test]
+    fn access_denied_keys() {
+        let mut skipped = super::SkippedEntries::default();
+        skipped.access_denied(
+            &RegistryRoot::CurrentUser,
+            r"HKCU\Software\Python\ExampleCorp".to_string(),
+        );
+        skipped.access_denied(
+            &RegistryRoot::LocalMachine,
+            r"HKLM\Software\Python\ExampleCorp\3.12".to_string(),
+        );
+        // Only the machine-wide keys are reported.
+        assert_eq!(
+            skipped.access_denied,
+            [r"HKLM\Software\Python\ExampleCorp\3.12"]
+        );
+    }
+
+    #[test]
+    fn executable_arguments() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("executable_arguments");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+        registry.register("ExampleCorp", "3.11", "3.11", r"C:\Example311\python.exe")?;
+        registry.register("ExampleCorp", "3.10", "3.10", r"C:\Example310\python.exe")?;
+        let install_path = |tag: &str| {
+            CURRENT_USER.create(format!(
+                r"{}\ExampleCorp\{tag}\InstallPath",
+                registry.python_key
+            ))
+        };
+        install_path("3.12")?
+            .set_multi_string(super::EXECUTABLE_ARGUMENTS_VALUE, &["-I", "-X utf8"])?;
+        install_path("3.11")?.set_string(super::EXECUTABLE_ARGUMENTS_VALUE, " -I  -s ")?;
+
+        let pythons = registry.run(registry_pythons)?;
+        assert_eq!(pythons[0].executable_arguments, ["-I", "-X utf8"]);
+        assert_eq!(pythons[1].executable_arguments, ["-I", "-s"]);
+        assert!(pythons[2].executable_arguments.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn registry_python_minor_versions() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("registry_python_minor_versions");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+        registry.register("OtherCorp", "3.12.4", "3.12.4", r"C:\Other312\python.exe")?;
+        registry.register("ExampleCorp", "3.11", "3.11", r"C:\Example311\python.exe")?;
+        registry.register("ExampleCorp", "Unknown", "", r"C:\Unknown\python.exe")?;
+
+        assert_eq!(
+            registry.run(super::registry_python_minor_versions),
+            std::collections::BTreeSet::from([(3, 11), (3, 12)])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn display_name_resource() -> anyhow::Result<()> {
+        let resource_file = std::env::current_exe()?;
+        let metadata = super::RegistryMetadata::default()
+            .with_display_name_resource(resource_file.clone(), 101);
+        assert_eq!(
+            metadata.indirect_display_name(),
+            Some(format!("@{},-101", resource_file.display()))
+        );
+
+        // Without the resource file, the plain display name is used.
+        let metadata = super::RegistryMetadata::default()
+            .with_display_name_resource(PathBuf::from(r"C:\uv-test\missing.dll"), 101);
+        assert_eq!(metadata.indirect_display_name(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn infer_windowed_executable() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let python = super::WindowsPython::new(dir.path().join("python.exe"), None);
+        assert_eq!(python.infer_windowed_executable(), None);
+
+        fs_err::write(dir.path().join("pythonw.exe"), "")?;
+        assert_eq!(
+            python.infer_windowed_executable(),
+            Some(dir.path().join("pythonw.exe"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn view_pointer_width() -> anyhow::Result<()> {
+        use target_lexicon::PointerWidth;
+
+        let registry = TestRegistry::new("view_pointer_width");
+        let tag = CURRENT_USER.create(format!(r"{}\ExampleCorp\3.12", registry.python_key))?;
+
+        // Entries in `WOW6432Node` are for 32-bit interpreters.
+        assert_eq!(
+            super::read_pointer_width("3.12", &tag, super::RegistryView::Wow64),
+            Some(PointerWidth::U32)
+        );
+        assert_eq!(
+            super::read_pointer_width("3.12", &tag, super::RegistryView::Shared),
+            None
+        );
+
+        // `SysArchitecture` takes precedence over the view.
+        tag.set_string("SysArchitecture", "64bit")?;
+        assert_eq!(
+            super::read_pointer_width("3.12", &tag, super::RegistryView::Wow64),
+            Some(PointerWidth::U64)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn validate_managed_registry() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("validate_managed_registry");
+        let dir = tempfile::tempdir()?;
+        let installation = ManagedPythonInstallation::from_path(
+            dir.path().join("cpython-3.12.8-windows-x86_64-none"),
+        )?;
+        let executable = installation.executable(false);
+        fs_err::create_dir_all(executable.parent().unwrap())?;
+        fs_err::write(&executable, "")?;
+        let mut errors = Vec::new();
+        registry.run(|| super::create_registry_entry(&installation, &mut errors))?;
+        assert!(errors.is_empty());
+        assert_eq!(registry.run(super::validate_managed_registry), []);
+
+        let tag = CURRENT_USER.create(format!(
+            r"{}\{}\CPython3.12.8",
+            registry.python_key,
+            crate::COMPANY_KEY
+        ))?;
+        tag.remove_value("SysArchitecture")?;
+        tag.set_string("SysVersion", "3.11.0")?;
+        assert_eq!(
+            registry.run(super::validate_managed_registry),
+            [
+                super::RegistryProblem::MissingValue {
+                    tag: "CPython3.12.8".to_string(),
+                    value: "SysArchitecture",
+                },
+                super::RegistryProblem::InvalidValue {
+                    tag: "CPython3.12.8".to_string(),
+                    value: "SysVersion",
+                    reason: "`3.11.0` doesn't match the `Version` `3.12.8`".to_string(),
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn find_registry_python_for() -> anyhow::Result<()> {
+        use crate::{ImplementationName, VersionRequest};
+
+        let registry = TestRegistry::new("find_registry_python_for");
+        registry.register("PythonCore", "3.12", "3.12.4", r"C:\Python3124\python.exe")?;
+        registry.register(
+            "PythonCore",
+            "3.12-32",
+            "3.12.8",
+            r"C:\Python3128\python.exe",
+        )?;
+        registry.register("PythonCore", "3.11", "3.11", r"C:\Python311\python.exe")?;
+        registry.register("ExampleCorp", "3.12", "3.12.9", r"C:\Example\python.exe")?;
+
+        let find = |implementation, request: &str| {
+            registry.run(|| {
+                super::find_registry_python_for(
+                    implementation,
+                    &VersionRequest::from_str(request).unwrap(),
+                )
+            })
+        };
+        // The Python of the unknown distribution isn't CPython.
+        assert_eq!(
+            find(ImplementationName::CPython, "3.12").map(|python| python.path),
+            Some(PathBuf::from(r"C:\Python3128\python.exe"))
+        );
+        assert!(find(ImplementationName::PyPy, "3.12").is_none());
+        assert!(find(ImplementationName::CPython, "3.10").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn merge_company() {
+        let company = |name: &str, display_name: Option<&str>| super::CompanyInfo {
+            root: RegistryRoot::LocalMachine,
+            name: name.to_string(),
+            display_name: display_name.map(ToString::to_string),
+            tag_count: 1,
+        };
+        let mut companies = Vec::new();
+        super::merge_company(&mut companies, company("Anaconda", None));
+        super::merge_company(&mut companies, company("anaconda", Some("Anaconda, Inc.")));
+        super::merge_company(&mut companies, company("ANACONDA", Some("Other")));
+        super::merge_company(&mut companies, company("PythonCore", None));
+
+        assert_eq!(companies.len(), 2);
+        assert_eq!(companies[0].name, "Anaconda");
+        assert_eq!(companies[0].display_name.as_deref(), Some("Anaconda, Inc."));
+        assert_eq!(companies[0].tag_count, 3);
+    }
+
+    #[test]
+    fn registry_pythons_visit() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("registry_pythons_visit");
+        registry.register("ExampleCorp", "3.12", "3.12", r"C:\Example312\python.exe")?;
+        registry.register("ExampleCorp", "3.13", "3.13", r"C:\Example313\python.exe")?;
+        // Entries without an executable are skipped.
+        CURRENT_USER
+            .create(format!(r"{}\ExampleCorp\3.11", registry.python_key))?
+            .set_string("SysVersion", "3.11")?;
+
+        let mut paths = Vec::new();
+        registry.run(|| super::registry_pythons_visit(|python| paths.push(python.path)))?;
+        paths.sort();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from(r"C:\Example312\python.exe"),
+                PathBuf::from(r"C:\Example313\python.exe")
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_pythons() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("legacy_pythons");
+        registry.register("PythonCore", "2.7", "2.7", r"C:\Python27\python.exe")?;
+        registry.register("PythonCore", "3.12", "3.12", r"C:\Python312\python.exe")?;
+
+        let pythons = registry.run(|| {
+            registry_pythons_with(&RegistryScanOptions::default().with_include_python2(true))
+        })?;
+        assert_eq!(
+            pythons
+                .iter()
+                .map(|python| (python.path.clone(), python.is_legacy()))
+                .collect::<Vec<_>>(),
+            [
+                (PathBuf::from(r"C:\Python312\python.exe"), false),
+                (PathBuf::from(r"C:\Python27\python.exe"), true),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn free_threaded() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("free_threaded");
+        registry.register(
+            "PythonCore",
+            "3.13t-32",
+            "3.13",
+            r"C:\Python313t-32\python.exe",
+        )?;
+        registry.register("PythonCore", "3.12", "3.12", r"C:\Python312\python.exe")?;
+        registry.register("ExampleCorp", "3.14", "3.14", r"C:\Example314\python.exe")?;
+        registry.register("ExampleCorp", "3.13t", "3.13", r"C:\Example313t\python.exe")?;
+        registry.register("OtherCorp", "3.13", "3.13", r"C:\Other313\python.exe")?;
+        let tag = |company: &str, tag: &str| {
+            CURRENT_USER.create(format!(r"{}\{company}\{tag}", registry.python_key))
+        };
+        tag("ExampleCorp", "3.14")?.set_u32(super::FREE_THREADED_VALUE, 1)?;
+        // The explicit value takes precedence over the tag.
+        tag("ExampleCorp", "3.13t")?.set_string(super::FREE_THREADED_VALUE, "false")?;
+        tag("OtherCorp", "3.13")?.set_string("DisplayName", "Other Python 3.13 (free-threaded)")?;
+
+        let pythons = registry.run(registry_pythons)?;
+        let free_threaded = |path: &str| {
+            pythons
+                .iter()
+                .find(|python| python.path == Path::new(path))
+                .unwrap()
+                .is_free_threaded()
+        };
+        assert!(free_threaded(r"C:\Python313t-32\python.exe"));
+        assert!(!free_threaded(r"C:\Python312\python.exe"));
+        assert!(free_threaded(r"C:\Example314\python.exe"));
+        assert!(!free_threaded(r"C:\Example313t\python.exe"));
+        assert!(free_threaded(r"C:\Other313\python.exe"));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_tag_is_per_installation() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("invalid_tag_is_per_installation");
+        let installation = cpython312()?;
+        let mut errors = Vec::new();
+        registry.run(|| {
+            super::create_registry_entry_as(
+                &RegistryRoot::CurrentUser,
+                &installation,
+                &super::RegistryMetadata::default(),
+                r"CPython\3.12.8",
+                &mut errors,
+            )
+        })?;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(&errors[0].0, installation.key());
+        Ok(())
+    }
+
+    #[test]
+    fn into_discovery_candidate() {
+        use target_lexicon::PointerWidth;
+
+        let mut python = super::WindowsPython::new(
+            PathBuf::from(r"C:\Python312\python.exe"),
+            Some(PythonVersion::from_str("3.12.4").unwrap()),
+        );
+        python.company = Some("PythonCore".to_string());
+        python.pointer_width = Some(PointerWidth::U64);
+
+        let candidate = python.into_discovery_candidate(crate::PythonSource::Registry);
+        assert_eq!(candidate.source(), crate::PythonSource::Registry);
+        assert_eq!(candidate.path(), Path::new(r"C:\Python312\python.exe"));
+        assert_eq!(
+            candidate.version(),
+            Some(&PythonVersion::from_str("3.12.4").unwrap())
+        );
+        assert_eq!(candidate.pointer_width(), Some(PointerWidth::U64));
+        assert_eq!(
+            candidate.implementation(),
+            Some(crate::ImplementationName::CPython)
+        );
+        // The entry doesn't tell the architecture.
+        assert_eq!(candidate.arch(), None);
+    }
+
+    #[test]
+    fn managed_version_precedence() -> anyhow::Result<()> {
+        let registry = TestRegistry::new("managed_version_precedence");
+        let entries = [
+            ("CPython3.13.0rc1", Some("3.13.0"), "3.13.0rc1"),
+            ("CPython3.12.8", Some("3.12.8"), "3.11.2"),
+            ("CPython3.11.9", None, "3.11.9"),
+        ];
+        for (tag, sys_version, version) in entries {
+            let tag_key = CURRENT_USER.create(format!(
+                r"{}\{}\{tag}",
+                registry.python_key,
+                crate::COMPANY_KEY
+            ))?;
+            if let Some(sys_version) = sys_version {
+                tag_key.set_string("SysVersion", sys_version)?;
+            }
+            tag_key.set_string("Version", version)?;
+            tag_key
+                .create("InstallPath")?
+                .set_string("ExecutablePath", &format!(r"C:\{tag}\python.exe"))?;
+        }
+
+        // Like for other entries, `SysVersion` is preferred unless `Version` is more specific, and
+        // `Version` is only used without `SysVersion` because uv always writes a Python version.
+        let pythons = registry.run(registry_pythons)?;
+        let versions: Vec<_> = pythons
+            .iter()
+            .map(|python| python.version.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(versions, ["3.13.0rc1", "3.12.8", "3.11.9"]);
+        Ok(())
     }
 }
@@ -233,6 +233,10 @@ impl EnvVars {
     /// Distributions can be read from a local directory by using the `file://` URL scheme.
     pub const UV_PYTHON_INSTALL_MIRROR: &'static str = "UV_PYTHON_INSTALL_MIRROR";
 
+    /// If set to `1` or `true`, uv will not discover Python installations registered in the
+    /// Windows registry, e.g., to only use managed Python installations.
+    pub const UV_PYTHON_NO_REGISTRY: &'static str = "UV_PYTHON_NO_REGISTRY";
+
     /// Managed PyPy installations are downloaded from [python.org](https://downloads.python.org/).
     ///
     /// This variable can be set to a mirror URL to use a
@@ -429,6 +433,9 @@ impl EnvVars {
     /// Used to look for Microsoft Store Pythons installations.
     pub const LOCALAPPDATA: &'static str = "LOCALAPPDATA";
 
+    /// The default Python version of the `py` launcher on Windows, e.g., `3.12`.
+    pub const PY_PYTHON: &'static str = "PY_PYTHON";
+
     /// Path to the `.git` directory. Ignored by `uv` when performing fetch.
     #[attr_hidden]
     pub const GIT_DIR: &'static str = "GIT_DIR";